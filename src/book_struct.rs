@@ -0,0 +1,524 @@
+//! Book-structure / table-of-contents extraction for reflowed CJK text.
+//!
+//! This runs *after* [`crate::reflow_cjk_paragraphs`] has merged wrapped
+//! lines back into paragraphs. It re-scans the reflowed text line-by-line
+//! and classifies candidate heading lines into a small ranked hierarchy:
+//! volume (卷) > part (部/篇) > chapter (章/回) > section (節/节), with a
+//! separate level-0 bucket for front/back matter (前言/序, 終章/尾聲).
+//!
+//! Two numbering regimes are recognized per heading line: CJK/text markers
+//! (第…章, 卷二, "Chapter 5") and dotted-digit outline numbers (`1`,
+//! `1.1`, `1.1.1`), where the depth of the dotted number sets the level.
+//! [`extract_book_struct`] picks a dominant regime per document (whichever
+//! has more candidates, ties going to the one whose numbers form the more
+//! consistent non-decreasing sequence) and drops stray
+//! candidates from the other regime that don't fit their own sequence, so
+//! an incidental "1.2"-looking line in otherwise CJK-marker prose doesn't
+//! leak into the outline. Genuinely mixed documents (e.g. "第三章" followed
+//! by "3.1 引言") keep both, since the minority regime's numbers still form
+//! a consistent sequence of their own.
+
+use crate::punct_sets::CJK_PUNCT_END;
+use pyo3::pyfunction;
+
+/// Maximum length (in chars) for a line to be considered a heading candidate.
+const MAX_HEADING_LEN: usize = 40;
+
+/// Front-matter keywords, matched against the line with internal whitespace
+/// removed so "前 言" still matches "前言".
+const FRONT_KEYWORDS: &[&str] = &["前言", "序言"];
+
+/// Back-matter keywords, matched the same way as [`FRONT_KEYWORDS`].
+const BACK_KEYWORDS: &[&str] = &["終章", "终章", "尾聲", "尾声"];
+
+const VOLUME_MARKERS: &[char] = &['卷'];
+const PART_MARKERS: &[char] = &['部', '篇'];
+const CHAPTER_MARKERS: &[char] = &['章', '回'];
+const SECTION_MARKERS: &[char] = &['节', '節'];
+
+const CJK_NUMERAL_CHARS: &str = "一二三四五六七八九十百千零〇";
+
+/// Closing brackets that may legally trail a heading (e.g. "第十章】").
+/// Excluded from the sentence-terminator check below.
+const CLOSING_BRACKETS: &[char] = &['）', '】', '》', '〗', '〕', '〉', '］', '｝'];
+
+/// Ranked structural level of a detected heading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeadingLevel {
+    FrontBack,
+    Volume,
+    Part,
+    Chapter,
+    Section,
+}
+
+impl HeadingLevel {
+    fn as_u32(self) -> u32 {
+        match self {
+            HeadingLevel::FrontBack => 0,
+            HeadingLevel::Volume => 1,
+            HeadingLevel::Part => 2,
+            HeadingLevel::Chapter => 3,
+            HeadingLevel::Section => 4,
+        }
+    }
+}
+
+/// Which of the two numbering regimes ([`classify_digit_outline_numbered`]
+/// vs. [`classify_text_marker_numbered`]) a candidate heading matched
+/// under. Used only
+/// for the whole-document consistency pass in [`extract_book_struct`];
+/// front/back matter has no regime since it never carries a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Regime {
+    DigitOutline,
+    TextMarker,
+}
+
+/// Extract a hierarchical outline from reflowed CJK text.
+///
+/// A heading line can match either numbering regime on its own, but a
+/// document overwhelmingly uses one of the two. Before building the
+/// outline, whichever regime has more candidates is treated as dominant;
+/// the minority regime's candidates are dropped as noise unless they
+/// demonstrate their own consistent, non-decreasing numeric sequence (two
+/// or more candidates that don't contradict each other) — a single
+/// candidate can't demonstrate a sequence on its own, so it reads as an
+/// incidental body-text match rather than deliberate sub-numbering. This
+/// keeps a single "1.2" that slipped past the length/punctuation filters
+/// out of an otherwise CJK-marker outline, while still allowing genuinely
+/// mixed documents (e.g. "第三章" followed by "3.1 引言", "3.2 ...") to
+/// keep both.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Reflowed text (usually the output of `reflow_cjk_paragraphs()`).
+///
+/// Returns
+/// -------
+/// List[Tuple[int, str, int]]
+///     One tuple per detected heading: `(level, title, char_offset)`,
+///     where `level` is 0 (front/back matter), 1 (volume), 2 (part),
+///     3 (chapter), or 4 (section), and `char_offset` is the char index
+///     of the heading's title (indent stripped) within `text`.
+#[pyfunction]
+pub fn extract_book_struct(text: &str) -> Vec<(u32, String, usize)> {
+    let mut candidates: Vec<(HeadingCandidate, usize)> = Vec::new();
+    let mut char_offset = 0usize;
+
+    for raw_line in text.split_inclusive('\n') {
+        let line_len_chars = raw_line.chars().count();
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+
+        let leading_ws = line.chars().take_while(|&c| c == ' ' || c == '\u{3000}').count();
+        let probe = line.trim_start_matches(|c| c == ' ' || c == '\u{3000}');
+
+        if let Some(candidate) = classify_heading_candidate(probe) {
+            candidates.push((candidate, char_offset + leading_ws));
+        }
+
+        char_offset += line_len_chars;
+    }
+
+    let digit_score = regime_score(&mut candidates, Regime::DigitOutline);
+    let text_score = regime_score(&mut candidates, Regime::TextMarker);
+    let dominant = if digit_score.count == text_score.count {
+        if digit_score.ratio() >= text_score.ratio() {
+            Regime::DigitOutline
+        } else {
+            Regime::TextMarker
+        }
+    } else if digit_score.count > text_score.count {
+        Regime::DigitOutline
+    } else {
+        Regime::TextMarker
+    };
+    let minority_score = match dominant {
+        Regime::DigitOutline => &text_score,
+        Regime::TextMarker => &digit_score,
+    };
+    // A single minority-regime candidate can't demonstrate a sequence of
+    // its own, so against an established dominant regime it's dropped
+    // outright as an incidental match rather than deliberate
+    // sub-numbering. Two or more get judged on whether they're
+    // consistent with each other, keeping only the ones that are.
+    let drop_lone_minority = minority_score.count == 1;
+    let minority_is_noisy = minority_score.count >= 2 && minority_score.ratio() < 1.0;
+
+    candidates
+        .into_iter()
+        .filter(|(candidate, _)| {
+            let Some(regime) = candidate.regime else {
+                return true; // front/back matter: always kept
+            };
+            if regime == dominant {
+                return true;
+            }
+            if drop_lone_minority {
+                return false;
+            }
+            !minority_is_noisy || candidate.fits_monotonic_sequence
+        })
+        .map(|(candidate, offset)| (candidate.level.as_u32(), candidate.title, offset))
+        .collect()
+}
+
+/// A heading line's classification plus the bookkeeping
+/// [`extract_book_struct`] needs to run its whole-document regime check.
+struct HeadingCandidate {
+    level: HeadingLevel,
+    title: String,
+    regime: Option<Regime>,
+    number: Option<u32>,
+    /// Set during [`regime_score`] to whether this candidate's own number
+    /// continues the running sequence for its regime, so a minority-regime
+    /// candidate that happens to fit can still be kept as genuine nesting.
+    fits_monotonic_sequence: bool,
+}
+
+/// Running tally of how consistently one regime's candidates form a
+/// non-decreasing numeric sequence in document order.
+struct RegimeScore {
+    count: usize,
+    consistent: usize,
+}
+
+impl RegimeScore {
+    /// Fraction of this regime's numbered candidates that continued the
+    /// sequence rather than breaking it; `1.0` (perfectly consistent) when
+    /// there's nothing to compare against.
+    fn ratio(&self) -> f64 {
+        if self.count == 0 {
+            1.0
+        } else {
+            self.consistent as f64 / self.count as f64
+        }
+    }
+}
+
+/// Scores `regime`'s candidates and, as a side effect, marks each one's
+/// [`HeadingCandidate::fits_monotonic_sequence`].
+fn regime_score(candidates: &mut [(HeadingCandidate, usize)], regime: Regime) -> RegimeScore {
+    let mut last_number = None;
+    let mut score = RegimeScore { count: 0, consistent: 0 };
+
+    for (candidate, _) in candidates.iter_mut() {
+        if candidate.regime != Some(regime) {
+            continue;
+        }
+        score.count += 1;
+        let fits = match (last_number, candidate.number) {
+            (Some(prev), Some(n)) => n >= prev,
+            _ => true, // no prior number (or this one is unnumbered) to contradict
+        };
+        if fits {
+            score.consistent += 1;
+            candidate.fits_monotonic_sequence = true;
+        }
+        if let Some(n) = candidate.number {
+            last_number = Some(n);
+        }
+    }
+
+    score
+}
+
+fn classify_heading_candidate(probe: &str) -> Option<HeadingCandidate> {
+    let trimmed = probe.trim_end();
+    if trimmed.is_empty() || trimmed.chars().count() > MAX_HEADING_LEN {
+        return None;
+    }
+    if contains_sentence_terminator(trimmed) {
+        return None;
+    }
+
+    if let Some(level) = classify_front_back(trimmed) {
+        return Some(HeadingCandidate {
+            level,
+            title: trimmed.to_string(),
+            regime: None,
+            number: None,
+            fits_monotonic_sequence: true,
+        });
+    }
+    if let Some((level, number)) = classify_digit_outline_numbered(trimmed) {
+        return Some(HeadingCandidate {
+            level,
+            title: trimmed.to_string(),
+            regime: Some(Regime::DigitOutline),
+            number,
+            fits_monotonic_sequence: false, // filled in by `regime_score` below
+        });
+    }
+    if let Some((level, number)) = classify_text_marker_numbered(trimmed) {
+        return Some(HeadingCandidate {
+            level,
+            title: trimmed.to_string(),
+            regime: Some(Regime::TextMarker),
+            number,
+            fits_monotonic_sequence: false,
+        });
+    }
+
+    None
+}
+
+fn classify_heading_line(probe: &str) -> Option<(HeadingLevel, String)> {
+    classify_heading_candidate(probe).map(|candidate| (candidate.level, candidate.title))
+}
+
+/// A heading must not carry a complete-sentence terminator. Reuses
+/// `CJK_PUNCT_END` but excludes closing brackets, which may legitimately
+/// trail a heading (e.g. "第十章】").
+fn contains_sentence_terminator(s: &str) -> bool {
+    s.chars()
+        .any(|c| CJK_PUNCT_END.contains(&c) && !CLOSING_BRACKETS.contains(&c))
+}
+
+fn classify_front_back(s: &str) -> Option<HeadingLevel> {
+    let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if compact == "序" || FRONT_KEYWORDS.iter().any(|&k| compact.starts_with(k)) {
+        return Some(HeadingLevel::FrontBack);
+    }
+    if BACK_KEYWORDS.iter().any(|&k| compact.ends_with(k)) {
+        return Some(HeadingLevel::FrontBack);
+    }
+
+    None
+}
+
+/// Pure-digital / hybrid outline numbers: `1`, `1.1`, `1.1.1 小節名`.
+/// Depth (number of dotted components) sets the level: a bare top-level
+/// number reads as a chapter, anything nested reads as a section. Also
+/// returns the first component, parsed as a number, for the whole-document
+/// monotonic-sequence check in [`extract_book_struct`].
+fn classify_digit_outline_numbered(s: &str) -> Option<(HeadingLevel, Option<u32>)> {
+    let bytes = s.as_bytes();
+    let mut i = 0usize;
+    let mut depth = 0usize;
+
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i == 0 {
+        return None;
+    }
+    depth += 1;
+    let first_component: Option<u32> = s[..i].parse().ok();
+
+    loop {
+        if i < bytes.len() && bytes[i] == b'.' {
+            let mut j = i + 1;
+            let mut saw_digit = false;
+            while j < bytes.len() && bytes[j].is_ascii_digit() {
+                saw_digit = true;
+                j += 1;
+            }
+            if !saw_digit {
+                break;
+            }
+            depth += 1;
+            i = j;
+        } else {
+            break;
+        }
+    }
+
+    // The number must be the whole token: followed by whitespace, or
+    // nothing else on the line.
+    if i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+        return None;
+    }
+
+    let level = if depth <= 1 {
+        HeadingLevel::Chapter
+    } else {
+        HeadingLevel::Section
+    };
+    Some((level, first_component))
+}
+
+/// CJK/text markers: "第…卷/部/篇/章/回/节", a bare "卷" followed directly
+/// by a numeral, or ASCII "Volume/Part/Chapter N". Also returns the
+/// numeral between the marker and its prefix, parsed as a number, for the
+/// whole-document monotonic-sequence check in [`extract_book_struct`].
+fn classify_text_marker_numbered(s: &str) -> Option<(HeadingLevel, Option<u32>)> {
+    let chars: Vec<char> = s.chars().collect();
+
+    for i in 0..chars.len() {
+        if chars[i] != '第' {
+            continue;
+        }
+        // "第" itself must appear near the start of the line.
+        if i > 10 {
+            continue;
+        }
+
+        for j in (i + 1)..chars.len() {
+            // Marker must follow "第" within a handful of characters.
+            if j - i > 6 {
+                break;
+            }
+            if let Some(level) = marker_level(chars[j]) {
+                if chars.len().saturating_sub(j + 1) <= 20 {
+                    let numeral: String = chars[(i + 1)..j].iter().collect();
+                    return Some((level, parse_numeral(&numeral)));
+                }
+            }
+        }
+    }
+
+    // Bare "卷" immediately followed by a numeral, e.g. "卷二", "卷3".
+    if let Some(rest) = s.strip_prefix('卷') {
+        if rest
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit() || CJK_NUMERAL_CHARS.contains(c))
+        {
+            let numeral: String = rest.chars().take_while(|c| *c != '\u{3000}' && !c.is_whitespace()).collect();
+            return Some((HeadingLevel::Volume, parse_numeral(&numeral)));
+        }
+    }
+
+    // ASCII "Volume N" / "Part N" / "Chapter N" (case-insensitive).
+    let lower = s.to_ascii_lowercase();
+    for (prefix, level) in [
+        ("volume", HeadingLevel::Volume),
+        ("part", HeadingLevel::Part),
+        ("chapter", HeadingLevel::Chapter),
+    ] {
+        if let Some(rest) = lower.strip_prefix(prefix) {
+            let rest = rest.trim_start();
+            if rest.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+                let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+                return Some((level, digits.parse().ok()));
+            }
+        }
+    }
+
+    None
+}
+
+fn marker_level(ch: char) -> Option<HeadingLevel> {
+    if VOLUME_MARKERS.contains(&ch) {
+        Some(HeadingLevel::Volume)
+    } else if PART_MARKERS.contains(&ch) {
+        Some(HeadingLevel::Part)
+    } else if CHAPTER_MARKERS.contains(&ch) {
+        Some(HeadingLevel::Chapter)
+    } else if SECTION_MARKERS.contains(&ch) {
+        Some(HeadingLevel::Section)
+    } else {
+        None
+    }
+}
+
+/// Parses a numeral between a marker and its prefix (e.g. the "三" in
+/// "第三章", the "12" in "第12章") into a number, trying plain ASCII digits
+/// first and falling back to [`parse_cjk_numeral`]. Returns `None` for
+/// anything that isn't one of the two (e.g. an empty gap).
+fn parse_numeral(s: &str) -> Option<u32> {
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+        return s.parse().ok();
+    }
+    parse_cjk_numeral(s)
+}
+
+/// Parses a CJK numeral in the 0-99 range: a single digit character, a bare
+/// "十" (10), "十X" (10+X), "X十" (X*10), or "X十Y" (X*10+Y). Doesn't
+/// attempt 百/千 or anything larger — book numbering rarely needs it, and a
+/// `None` here just means the candidate isn't scored in the monotonic-
+/// sequence check, not that it's rejected as a heading.
+fn parse_cjk_numeral(s: &str) -> Option<u32> {
+    fn digit(c: char) -> Option<u32> {
+        match c {
+            '零' | '〇' => Some(0),
+            '一' => Some(1),
+            '二' => Some(2),
+            '三' => Some(3),
+            '四' => Some(4),
+            '五' => Some(5),
+            '六' => Some(6),
+            '七' => Some(7),
+            '八' => Some(8),
+            '九' => Some(9),
+            _ => None,
+        }
+    }
+
+    let chars: Vec<char> = s.chars().collect();
+    match chars.as_slice() {
+        [] => None,
+        [c] if *c == '十' => Some(10),
+        [c] => digit(*c),
+        [a, b] if *a == '十' => digit(*b).map(|ones| 10 + ones),
+        [a, b] if *b == '十' => digit(*a).map(|tens| tens * 10),
+        [a, b, c] if *b == '十' => {
+            let tens = digit(*a)?;
+            let ones = digit(*c)?;
+            Some(tens * 10 + ones)
+        }
+        _ => None,
+    }
+}
+
+/// Classifies a single already-reflowed line as a heading and returns its
+/// hierarchical level (0 front/back matter, 1 volume, 2 part, 3 chapter,
+/// 4 section), reusing the exact classification [`extract_book_struct`]
+/// scans whole documents with. Lets `reflow_cjk_paragraphs`'s Markdown/Org
+/// heading-prefix rendering stay consistent with the levels `build_outline`
+/// reports for the same heading.
+pub(crate) fn heading_level(line: &str) -> Option<u32> {
+    classify_heading_line(line).map(|(level, _)| level.as_u32())
+}
+
+/// Alias of [`extract_book_struct`] under the name used to ask for it: a
+/// navigable table of contents built straight from a raw PDF dump,
+/// without a separate structural parser.
+///
+/// See [`extract_book_struct`] for parameters and return shape.
+#[pyfunction]
+pub fn build_outline(text: &str) -> Vec<(u32, String, usize)> {
+    extract_book_struct(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_book_struct_drops_stray_digit_outline_in_text_marker_document() {
+        let text = "第一章 起始\n正文正文\n1.2 版本号仅供参考\n正文正文\n第二章 承接\n正文正文\n";
+        let outline = extract_book_struct(text);
+        let titles: Vec<&str> = outline.iter().map(|(_, title, _)| title.as_str()).collect();
+        assert_eq!(titles, vec!["第一章 起始", "第二章 承接"]);
+    }
+
+    #[test]
+    fn test_extract_book_struct_keeps_consistent_digit_outline() {
+        let text = "1 引言\n正文\n2 方法\n正文\n3 结论\n正文\n";
+        let outline = extract_book_struct(text);
+        let titles: Vec<&str> = outline.iter().map(|(_, title, _)| title.as_str()).collect();
+        assert_eq!(titles, vec!["1 引言", "2 方法", "3 结论"]);
+    }
+
+    #[test]
+    fn test_extract_book_struct_keeps_genuinely_mixed_regimes() {
+        let text = "第三章 框架\n正文\n3.1 引言\n正文\n3.2 背景\n正文\n第四章 实验\n正文\n";
+        let outline = extract_book_struct(text);
+        let titles: Vec<&str> = outline.iter().map(|(_, title, _)| title.as_str()).collect();
+        assert_eq!(titles, vec!["第三章 框架", "3.1 引言", "3.2 背景", "第四章 实验"]);
+    }
+
+    #[test]
+    fn test_parse_cjk_numeral() {
+        assert_eq!(parse_cjk_numeral("三"), Some(3));
+        assert_eq!(parse_cjk_numeral("十"), Some(10));
+        assert_eq!(parse_cjk_numeral("十五"), Some(15));
+        assert_eq!(parse_cjk_numeral("二十"), Some(20));
+        assert_eq!(parse_cjk_numeral("二十三"), Some(23));
+        assert_eq!(parse_cjk_numeral(""), None);
+    }
+}
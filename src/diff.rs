@@ -0,0 +1,246 @@
+//! Structured edit-diff ("dry run") output for auditing what a cleanup
+//! pass changed, built on the classic Myers shortest-edit-script
+//! algorithm: explore diagonals `k`, where `v[k]` holds the furthest-
+//! reaching `x` on that diagonal for edit distance `d`, increasing `d`
+//! until the end of both sequences is reached, then backtrack the trace
+//! to recover a run of `Equal`/`Delete`/`Insert` steps.
+//!
+//! This is deliberately generic (`diff_chars`/`diff_text` take any two
+//! strings) rather than threaded into `reflow_cjk_paragraphs` or
+//! `reflow_paragraphs` directly, so those pipelines keep their existing
+//! fast path — callers who want an audit trail run the pipeline as
+//! before, then diff its input against its output themselves.
+
+use pyo3::pyfunction;
+
+/// One step of an edit script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    /// Unchanged span, present in both the original and the result.
+    Equal,
+    /// Span present in the original but removed from the result.
+    Delete,
+    /// Span present in the result but not in the original.
+    Insert,
+}
+
+impl EditKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            EditKind::Equal => "equal",
+            EditKind::Delete => "delete",
+            EditKind::Insert => "insert",
+        }
+    }
+}
+
+/// One run of consecutive same-kind steps in an edit script.
+///
+/// `original_range` is a byte range into the *original* string: for
+/// `Equal`/`Delete` it's the span's own bytes; for a pure `Insert` (no
+/// original text consumed) it's an empty range at the insertion point.
+/// `new_text` holds the span's text in the *result* string: for `Equal`/
+/// `Insert` that's the span itself; for `Delete` it's empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditSpan {
+    pub kind: EditKind,
+    pub original_range: (usize, usize),
+    pub new_text: String,
+}
+
+/// Myers' O((N+M)D) shortest-edit-script search. Returns the full `v`
+/// trace, one snapshot per edit distance `d`, needed to backtrack a path
+/// afterwards. `max_d = a.len() + b.len()` bounds both the loop and the
+/// diagonal offset used to keep `k` (which ranges over `-d..=d`) as a
+/// non-negative index into `v`.
+fn shortest_edit_trace(a: &[char], b: &[char]) -> Vec<Vec<i64>> {
+    let n = a.len() as i64;
+    let m = b.len() as i64;
+    let max_d = n + m;
+
+    let mut v = vec![0i64; 2 * max_d as usize + 1];
+    let mut trace = Vec::new();
+
+    for d in 0..=max_d {
+        trace.push(v.clone());
+
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + max_d) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            v[idx] = x;
+
+            if x >= n && y >= m {
+                return trace;
+            }
+
+            k += 2;
+        }
+    }
+
+    trace
+}
+
+/// Backtracks `trace` from `(a.len(), b.len())` to `(0, 0)`, returning the
+/// path as a list of `(prev_x, prev_y, x, y)` steps in forward order. Each
+/// step is a diagonal move (`x == prev_x + 1 && y == prev_y + 1`, an
+/// `Equal`), a horizontal move (`x == prev_x + 1`, a `Delete` of `a[prev_x]`),
+/// or a vertical move (`y == prev_y + 1`, an `Insert` of `b[prev_y]`).
+fn backtrack_path(a: &[char], b: &[char], trace: &[Vec<i64>]) -> Vec<(i64, i64, i64, i64)> {
+    let n = a.len() as i64;
+    let m = b.len() as i64;
+    let max_d = n + m;
+
+    let mut x = n;
+    let mut y = m;
+    let mut path = Vec::new();
+
+    for d in (0..trace.len()).rev() {
+        let v = &trace[d];
+        let d = d as i64;
+        let k = x - y;
+
+        let prev_k = if k == -d || (k != d && v[(k - 1 + max_d) as usize] < v[(k + 1 + max_d) as usize])
+        {
+            k + 1
+        } else {
+            k - 1
+        };
+
+        let prev_x = v[(prev_k + max_d) as usize];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            path.push((x - 1, y - 1, x, y));
+            x -= 1;
+            y -= 1;
+        }
+
+        if d > 0 {
+            path.push((prev_x, prev_y, x, y));
+        }
+
+        x = prev_x;
+        y = prev_y;
+    }
+
+    path.reverse();
+    path
+}
+
+/// Diffs two char sequences and returns the shortest edit script as
+/// run-length-encoded `Equal`/`Delete`/`Insert` spans (consecutive steps
+/// of the same kind merged into one `EditSpan`). `original_range` is a
+/// *char* range here; [`diff_text`] converts it to a byte range.
+fn diff_char_spans(a: &[char], b: &[char]) -> Vec<(EditKind, usize, usize, usize, usize)> {
+    if a.is_empty() && b.is_empty() {
+        // `max_d` would be 0 here, leaving no room in `shortest_edit_trace`'s
+        // `v` for the `k+1`/`k-1` neighbor probes — short-circuit instead.
+        return Vec::new();
+    }
+
+    let trace = shortest_edit_trace(a, b);
+    let path = backtrack_path(a, b, &trace);
+
+    let mut spans: Vec<(EditKind, usize, usize, usize, usize)> = Vec::new();
+
+    for (px, py, x, y) in path {
+        let (px, py, x, y) = (px as usize, py as usize, x as usize, y as usize);
+
+        let kind = if x == px + 1 && y == py + 1 {
+            EditKind::Equal
+        } else if x == px + 1 && y == py {
+            EditKind::Delete
+        } else {
+            EditKind::Insert
+        };
+
+        match spans.last_mut() {
+            Some((last_kind, _a_start, a_end, _b_start, b_end)) if *last_kind == kind => {
+                *a_end = x;
+                *b_end = y;
+            }
+            _ => spans.push((kind, px, x, py, y)),
+        }
+    }
+
+    spans
+}
+
+/// Diffs `original` against `modified` and returns the edit script as
+/// byte-range [`EditSpan`]s, so a Python caller can render a highlighted
+/// preview or selectively reject individual edits (e.g. veto a
+/// `collapse_repeated_token` run that ate a legitimate reduplicated word)
+/// before applying `modified`.
+///
+/// Compares by Unicode scalar value (`char`), not by byte or token, so
+/// ranges always land on char boundaries regardless of how the caller's
+/// cleanup pipeline tokenized its input.
+pub fn diff_text(original: &str, modified: &str) -> Vec<EditSpan> {
+    let a: Vec<char> = original.chars().collect();
+    let b: Vec<char> = modified.chars().collect();
+
+    let a_byte_offsets: Vec<usize> = original
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(original.len()))
+        .collect();
+
+    diff_char_spans(&a, &b)
+        .into_iter()
+        .map(|(kind, a_start, a_end, b_start, b_end)| {
+            let original_range = (a_byte_offsets[a_start], a_byte_offsets[a_end]);
+            let new_text = match kind {
+                EditKind::Delete => String::new(),
+                EditKind::Equal | EditKind::Insert => b[b_start..b_end].iter().collect(),
+            };
+            EditSpan {
+                kind,
+                original_range,
+                new_text,
+            }
+        })
+        .collect()
+}
+
+/// Python-facing form of [`diff_text`]: same edit script, each
+/// [`EditSpan`] flattened to a `(kind, start, end, new_text)` tuple —
+/// `kind` is `"equal"`, `"delete"`, or `"insert"`, `start`/`end` are byte
+/// offsets into `original`.
+///
+/// Parameters
+/// ----------
+/// original : str
+///     Text before cleanup (e.g. raw PDF/OCR extraction).
+/// modified : str
+///     Text after cleanup (e.g. the output of `reflow_cjk_paragraphs()`).
+///
+/// Returns
+/// -------
+/// List[Tuple[str, int, int, str]]
+///     One tuple per edit span, in document order.
+#[pyfunction]
+pub fn diff_text_edits(original: &str, modified: &str) -> Vec<(String, usize, usize, String)> {
+    diff_text(original, modified)
+        .into_iter()
+        .map(|span| {
+            (
+                span.kind.as_str().to_string(),
+                span.original_range.0,
+                span.original_range.1,
+                span.new_text,
+            )
+        })
+        .collect()
+}
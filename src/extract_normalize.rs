@@ -0,0 +1,104 @@
+//! Cleans up encoding artifacts that `pdf-extract` frequently emits, so
+//! that the CJK-ratio heuristics in [`crate::cjk_text`] (`is_mostly_cjk`,
+//! `is_mixed_cjk_ascii`) classify lines correctly instead of treating a
+//! ligature glyph or a fullwidth Latin run as "not really ASCII".
+
+use pyo3::pyfunction;
+
+/// Which artifact classes [`normalize_extracted_text`] should clean up.
+/// Both default to `false` so existing callers keep byte-faithful output
+/// unless they opt in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeOptions {
+    /// Expand Unicode presentation-form ligatures (U+FB00–FB06, e.g. ﬁ→"fi")
+    /// to their constituent letters.
+    pub expand_ligatures: bool,
+    /// Fold fullwidth Latin letters/digits to halfwidth. Fullwidth digits
+    /// fold using the same `０`-`９` range `is_digit_ascii_or_fullwidth`
+    /// checks, so CJK-ratio classification thresholds don't shift.
+    pub fold_fullwidth_ascii: bool,
+}
+
+/// Normalizes PDF-extraction artifacts in `text` per `options`.
+pub fn normalize_extracted_text(text: &str, options: NormalizeOptions) -> String {
+    if !options.expand_ligatures && !options.fold_fullwidth_ascii {
+        return text.to_owned();
+    }
+
+    let mut out = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        if options.expand_ligatures {
+            if let Some(expanded) = expand_ligature(ch) {
+                out.push_str(expanded);
+                continue;
+            }
+        }
+        if options.fold_fullwidth_ascii {
+            if let Some(folded) = fold_fullwidth_ascii_char(ch) {
+                out.push(folded);
+                continue;
+            }
+        }
+        out.push(ch);
+    }
+
+    out
+}
+
+/// Expands a single presentation-form ligature (U+FB00–FB06) to its
+/// constituent letters, or `None` if `ch` isn't one.
+fn expand_ligature(ch: char) -> Option<&'static str> {
+    match ch {
+        '\u{FB00}' => Some("ff"),
+        '\u{FB01}' => Some("fi"),
+        '\u{FB02}' => Some("fl"),
+        '\u{FB03}' => Some("ffi"),
+        '\u{FB04}' => Some("ffl"),
+        '\u{FB05}' => Some("st"),
+        '\u{FB06}' => Some("st"),
+        _ => None,
+    }
+}
+
+/// Folds a fullwidth Latin letter or digit to its halfwidth ASCII
+/// equivalent, or `None` if `ch` isn't one. The digit range matches
+/// `cjk_text::is_digit_ascii_or_fullwidth`.
+fn fold_fullwidth_ascii_char(ch: char) -> Option<char> {
+    match ch {
+        'Ａ'..='Ｚ' | 'ａ'..='ｚ' | '０'..='９' => char::from_u32(ch as u32 - 0xFEE0),
+        _ => None,
+    }
+}
+
+/// Normalizes PDF-extraction artifacts in already-extracted text.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Text to normalize (usually from `extract_pdf_text()` or similar).
+/// expand_ligatures : bool, default True
+///     Expand Unicode presentation-form ligatures (U+FB00-FB06, e.g.
+///     ﬁ→"fi") to their constituent letters.
+/// fold_fullwidth_ascii : bool, default False
+///     Fold fullwidth Latin letters/digits to halfwidth.
+///
+/// Returns
+/// -------
+/// str
+#[pyfunction]
+#[pyo3(name = "normalize_extracted_text")]
+#[pyo3(signature = (text, expand_ligatures=true, fold_fullwidth_ascii=false))]
+pub fn normalize_extracted_text_py(
+    text: &str,
+    expand_ligatures: bool,
+    fold_fullwidth_ascii: bool,
+) -> String {
+    normalize_extracted_text(
+        text,
+        NormalizeOptions {
+            expand_ligatures,
+            fold_fullwidth_ascii,
+        },
+    )
+}
@@ -0,0 +1,302 @@
+//! Pluggable PDF text-extraction backends.
+//!
+//! `pdf-extract` is pure Rust and the default, but it silently returns
+//! empty or garbled text for PDFs with missing/broken ToUnicode CMaps —
+//! common in scanned-then-printed CJK books. [`PdfiumBackend`] wraps
+//! PDFium for those. [`BackendKind::Auto`] tries the pure-Rust backend
+//! first and only pays PDFium's cost on pages that actually need it,
+//! replacing the old all-or-nothing fallback in
+//! `extract_pdf_pages_with_callback` that only fired when the whole
+//! document had a 0-page tree.
+
+use crate::cjk_text::contains_any_cjk_str;
+use pdf_extract::Document;
+use pyo3::{exceptions, PyErr, PyResult};
+
+/// A PDF text-extraction engine.
+///
+/// Implementations take care of their own document loading/decryption
+/// per call, so callers don't need to hold a document handle open across
+/// the trait boundary.
+pub trait PdfBackend {
+    /// Extracts the concatenated text of every page.
+    fn extract_text(&self, path: &str, password: Option<&str>) -> PyResult<String>;
+
+    /// Extracts text page-by-page, in reading order.
+    fn extract_pages(&self, path: &str, password: Option<&str>) -> PyResult<Vec<String>>;
+
+    /// Returns the document's page count.
+    fn page_count(&self, path: &str, password: Option<&str>) -> PyResult<usize>;
+}
+
+/// Loads a PDF document, transparently decrypting it if needed.
+///
+/// Many distributed PDFs are encrypted with the "empty user password"
+/// scheme, which still blocks extraction unless something decrypts it.
+/// When the document reports encryption, this attempts decryption with
+/// `password` (or an empty string when `None`) before returning it.
+pub(crate) fn load_and_decrypt(path: &str, password: Option<&str>) -> PyResult<Document> {
+    let mut doc = Document::load(path).map_err(|e| {
+        let msg = e.to_string();
+        let is_not_found = msg.contains("No such file")
+            || msg.contains("cannot find the file")
+            || msg.contains("os error 2");
+
+        if is_not_found {
+            exceptions::PyFileNotFoundError::new_err(path.to_string())
+        } else {
+            exceptions::PyRuntimeError::new_err(format!("Failed to open PDF '{}': {e}", path))
+        }
+    })?;
+
+    if doc.is_encrypted() {
+        doc.decrypt(password.unwrap_or("")).map_err(|e| {
+            let msg = e.to_string().to_lowercase();
+            if msg.contains("password") {
+                exceptions::PyRuntimeError::new_err(format!(
+                    "Incorrect password for encrypted PDF '{}'.",
+                    path
+                ))
+            } else {
+                exceptions::PyRuntimeError::new_err(format!(
+                    "PDF '{}' uses an unsupported encryption filter: {e}",
+                    path
+                ))
+            }
+        })?;
+    }
+
+    Ok(doc)
+}
+
+/// The default backend: pure-Rust extraction via `pdf-extract`.
+pub struct PureRustBackend;
+
+impl PdfBackend for PureRustBackend {
+    fn extract_text(&self, path: &str, password: Option<&str>) -> PyResult<String> {
+        let doc = load_and_decrypt(path, password)?;
+        let page_numbers: Vec<u32> = doc.get_pages().keys().copied().collect();
+
+        if page_numbers.is_empty() {
+            pdf_extract::extract_text(path).map_err(|e| {
+                exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to extract text from PDF '{}': {e}",
+                    path
+                ))
+            })
+        } else {
+            doc.extract_text(&page_numbers).map_err(|e| {
+                exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to extract text from PDF '{}': {e}",
+                    path
+                ))
+            })
+        }
+    }
+
+    fn extract_pages(&self, path: &str, password: Option<&str>) -> PyResult<Vec<String>> {
+        let doc = load_and_decrypt(path, password)?;
+        let page_numbers: Vec<u32> = doc.get_pages().keys().copied().collect();
+
+        if page_numbers.is_empty() {
+            pdf_extract::extract_text_by_pages(path).map_err(|e| {
+                exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to extract text by pages from PDF '{}': {e}",
+                    path
+                ))
+            })
+        } else {
+            page_numbers
+                .iter()
+                .map(|&n| {
+                    doc.extract_text(&[n]).map_err(|e| {
+                        exceptions::PyRuntimeError::new_err(format!(
+                            "Failed to extract text by pages from PDF '{}': {e}",
+                            path
+                        ))
+                    })
+                })
+                .collect()
+        }
+    }
+
+    fn page_count(&self, path: &str, password: Option<&str>) -> PyResult<usize> {
+        let doc = load_and_decrypt(path, password)?;
+        let page_numbers = doc.get_pages().len();
+
+        if page_numbers > 0 {
+            return Ok(page_numbers);
+        }
+
+        Ok(pdf_extract::extract_text_by_pages(path)
+            .map_err(|e| {
+                exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to determine page count of PDF '{}': {e}",
+                    path
+                ))
+            })?
+            .len())
+    }
+}
+
+/// Fallback backend for PDFs `PureRustBackend` can't read: binds to
+/// Google's PDFium via `pdfium-render`, which has its own glyph/CMap
+/// handling independent of `lopdf`'s.
+pub struct PdfiumBackend;
+
+impl PdfiumBackend {
+    fn bind() -> PyResult<pdfium_render::prelude::Pdfium> {
+        pdfium_render::prelude::Pdfium::bind_to_system_library()
+            .map(pdfium_render::prelude::Pdfium::new)
+            .map_err(|e| {
+                exceptions::PyRuntimeError::new_err(format!(
+                    "Failed to load the PDFium backend (is the PDFium shared library installed?): {e}"
+                ))
+            })
+    }
+
+    fn load<'a>(
+        pdfium: &'a pdfium_render::prelude::Pdfium,
+        path: &str,
+        password: Option<&str>,
+    ) -> PyResult<pdfium_render::prelude::PdfDocument<'a>> {
+        pdfium
+            .load_pdf_from_file(path, password)
+            .map_err(|e| exceptions::PyRuntimeError::new_err(format!(
+                "PDFium failed to open PDF '{}': {e}",
+                path
+            )))
+    }
+}
+
+impl PdfBackend for PdfiumBackend {
+    fn extract_text(&self, path: &str, password: Option<&str>) -> PyResult<String> {
+        Ok(self.extract_pages(path, password)?.join("\n\n"))
+    }
+
+    fn extract_pages(&self, path: &str, password: Option<&str>) -> PyResult<Vec<String>> {
+        let pdfium = Self::bind()?;
+        let document = Self::load(&pdfium, path, password)?;
+
+        document
+            .pages()
+            .iter()
+            .map(|page| {
+                page.text().map(|t| t.all()).map_err(|e| {
+                    exceptions::PyRuntimeError::new_err(format!(
+                        "PDFium failed to extract text from PDF '{}': {e}",
+                        path
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    fn page_count(&self, path: &str, password: Option<&str>) -> PyResult<usize> {
+        let pdfium = Self::bind()?;
+        let document = Self::load(&pdfium, path, password)?;
+        Ok(document.pages().len() as usize)
+    }
+}
+
+/// Which [`PdfBackend`] the Python-facing extraction functions should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    /// Try `pure-rust` first; retry any page it leaves empty/garbled
+    /// through `pdfium`.
+    Auto,
+    PureRust,
+    Pdfium,
+}
+
+impl BackendKind {
+    /// Parses the `backend` selector string accepted by the Python
+    /// extraction functions ("auto" / "pure-rust" / "pdfium").
+    pub fn parse(s: &str) -> PyResult<Self> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "pure-rust" => Ok(Self::PureRust),
+            "pdfium" => Ok(Self::Pdfium),
+            other => Err(exceptions::PyValueError::new_err(format!(
+                "Invalid backend '{}'; expected 'auto', 'pure-rust', or 'pdfium'",
+                other
+            ))),
+        }
+    }
+}
+
+/// A page's extracted text is treated as needing a PDFium retry when it's
+/// blank, or contains no CJK at all — `pdf-extract` tends to leave
+/// CJK runs as empty/near-empty strings when a PDF's ToUnicode CMap can't
+/// resolve them, rather than raising an error.
+fn page_looks_garbled(text: &str) -> bool {
+    text.trim().is_empty() || !contains_any_cjk_str(text)
+}
+
+/// Extracts page text per [`BackendKind::Auto`]'s policy: run
+/// `PureRustBackend` first, then retry only the pages it left
+/// empty/garbled through `PdfiumBackend`. The PDFium pass is skipped
+/// entirely (and its cost never paid) if every page already looks fine.
+pub fn extract_pages_auto(path: &str, password: Option<&str>) -> PyResult<Vec<String>> {
+    let mut pages = PureRustBackend.extract_pages(path, password)?;
+    let needs_retry: Vec<usize> = pages
+        .iter()
+        .enumerate()
+        .filter(|(_, text)| page_looks_garbled(text))
+        .map(|(i, _)| i)
+        .collect();
+
+    if needs_retry.is_empty() {
+        return Ok(pages);
+    }
+
+    let pdfium_pages = PdfiumBackend.extract_pages(path, password)?;
+    for idx in needs_retry {
+        if let Some(retried) = pdfium_pages.get(idx) {
+            pages[idx] = retried.clone();
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Resolves `kind` to a concrete one-call extraction, applying the
+/// [`BackendKind::Auto`] per-page retry policy where relevant.
+pub fn extract_text_with_backend(
+    kind: BackendKind,
+    path: &str,
+    password: Option<&str>,
+) -> PyResult<String> {
+    match kind {
+        BackendKind::Auto => Ok(extract_pages_auto(path, password)?.join("\n\n")),
+        BackendKind::PureRust => PureRustBackend.extract_text(path, password),
+        BackendKind::Pdfium => PdfiumBackend.extract_text(path, password),
+    }
+}
+
+/// Resolves `kind` to a concrete page-by-page extraction, applying the
+/// [`BackendKind::Auto`] per-page retry policy where relevant.
+pub fn extract_pages_with_backend(
+    kind: BackendKind,
+    path: &str,
+    password: Option<&str>,
+) -> PyResult<Vec<String>> {
+    match kind {
+        BackendKind::Auto => extract_pages_auto(path, password),
+        BackendKind::PureRust => PureRustBackend.extract_pages(path, password),
+        BackendKind::Pdfium => PdfiumBackend.extract_pages(path, password),
+    }
+}
+
+/// Resolves `kind` to a page count, preferring whichever backend the
+/// policy would actually use so the count isn't paid twice.
+pub fn page_count_with_backend(
+    kind: BackendKind,
+    path: &str,
+    password: Option<&str>,
+) -> PyResult<usize> {
+    match kind {
+        BackendKind::Auto | BackendKind::PureRust => PureRustBackend.page_count(path, password),
+        BackendKind::Pdfium => PdfiumBackend.page_count(path, password),
+    }
+}
@@ -0,0 +1,141 @@
+//! Per-page PDF content classification, so callers can tell a text page
+//! from a scanned/image-only one before deciding whether it needs OCR.
+//!
+//! This replaces the all-or-nothing fallback in
+//! `extract_pdf_pages_with_callback` (which only engaged when the whole
+//! document's page tree was empty) with pdfminer-style per-page object
+//! accounting: a page is `likely_scanned` when it carries image content
+//! but the text layer is (near-)empty, rather than the document as a
+//! whole failing to extract anything.
+
+use crate::cjk_text::{contains_any_cjk_str, is_mostly_cjk};
+use crate::pdf_backend::{extract_pages_with_backend, load_and_decrypt, BackendKind};
+use pyo3::{pyclass, pyfunction, pymethods, PyResult};
+
+/// Classification of a single PDF page's extracted content.
+#[pyclass(get_all)]
+#[derive(Debug, Clone)]
+pub struct PageInfo {
+    /// 1-based page number.
+    pub page_number: usize,
+    /// Length (in chars) of the page's extracted text.
+    pub text_len: usize,
+    /// Whether the extracted text is mostly CJK, per
+    /// `cjk_text::is_mostly_cjk`.
+    pub is_mostly_cjk: bool,
+    /// Whether the page carries image XObjects.
+    pub has_image: bool,
+    /// True when the page has image content but (near-)zero extractable
+    /// text — i.e. it's a scan that still needs OCR.
+    pub likely_scanned: bool,
+}
+
+#[pymethods]
+impl PageInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "PageInfo(page_number={}, text_len={}, is_mostly_cjk={}, has_image={}, likely_scanned={})",
+            self.page_number, self.text_len, self.is_mostly_cjk, self.has_image, self.likely_scanned
+        )
+    }
+}
+
+/// Document-level rollup of [`PageInfo`], so a UI can warn up front that a
+/// PDF is image-only without inspecting every page itself.
+#[pyclass(get_all)]
+#[derive(Debug, Clone)]
+pub struct PdfContentSummary {
+    pub total_pages: usize,
+    pub text_pages: usize,
+    pub scanned_pages: usize,
+    pub pages: Vec<PageInfo>,
+}
+
+#[pymethods]
+impl PdfContentSummary {
+    fn __repr__(&self) -> String {
+        format!(
+            "PdfContentSummary(total_pages={}, text_pages={}, scanned_pages={})",
+            self.total_pages, self.text_pages, self.scanned_pages
+        )
+    }
+}
+
+/// A page counts as having near-zero extractable text if its trimmed
+/// length falls at or below this many chars — short enough that it's
+/// almost certainly a running header/footer artifact, not real content.
+const NEAR_EMPTY_TEXT_THRESHOLD: usize = 2;
+
+/// Classifies every page of the PDF at `path` for scanned/image-only
+/// content, using `backend` to extract text (see `extract_pdf_text()`).
+///
+/// Parameters
+/// ----------
+/// path : str
+///     Path to the PDF file on disk.
+/// password : str, optional
+///     Password to decrypt the PDF with, if it is encrypted.
+/// backend : str, default "auto"
+///     Which extraction engine to use; see `extract_pdf_text()`.
+///
+/// Returns
+/// -------
+/// PdfContentSummary
+#[pyfunction]
+#[pyo3(signature = (path, password=None, backend="auto"))]
+pub fn classify_pdf_pages(
+    path: &str,
+    password: Option<&str>,
+    backend: &str,
+) -> PyResult<PdfContentSummary> {
+    let texts = extract_pages_with_backend(BackendKind::parse(backend)?, path, password)?;
+    let pages = classify_page_texts(path, password, &texts)?;
+
+    let total_pages = pages.len();
+    let scanned_pages = pages.iter().filter(|p| p.likely_scanned).count();
+    let text_pages = total_pages - scanned_pages;
+
+    Ok(PdfContentSummary {
+        total_pages,
+        text_pages,
+        scanned_pages,
+        pages,
+    })
+}
+
+/// Classifies already-extracted page `texts` against `path`'s image
+/// content, without re-running extraction. Shared by [`classify_pdf_pages`]
+/// and `extract_pdf_pages_with_callback`'s `classify` option so neither
+/// pays for a second extraction pass.
+pub(crate) fn classify_page_texts(
+    path: &str,
+    password: Option<&str>,
+    texts: &[String],
+) -> PyResult<Vec<PageInfo>> {
+    let doc = load_and_decrypt(path, password)?;
+    let page_ids: Vec<_> = doc.get_pages().into_values().collect();
+
+    Ok(texts
+        .iter()
+        .enumerate()
+        .map(|(idx, text)| {
+            let trimmed = text.trim();
+            let has_image = page_ids
+                .get(idx)
+                .map(|&id| {
+                    doc.get_page_images(id)
+                        .map(|images| !images.is_empty())
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false);
+
+            PageInfo {
+                page_number: idx + 1,
+                text_len: trimmed.chars().count(),
+                is_mostly_cjk: contains_any_cjk_str(trimmed) && is_mostly_cjk(trimmed),
+                has_image,
+                likely_scanned: has_image && trimmed.chars().count() <= NEAR_EMPTY_TEXT_THRESHOLD,
+            }
+        })
+        .collect())
+}
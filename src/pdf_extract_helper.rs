@@ -1,108 +1,232 @@
-use pdf_extract::Document;
-use pyo3::{exceptions, pyfunction, Py, PyAny, PyResult, Python};
+use crate::extract_normalize::{normalize_extracted_text, NormalizeOptions};
+use crate::pdf_backend::{
+    extract_pages_with_backend, extract_text_with_backend, BackendKind,
+};
+use crate::pdf_classify::classify_page_texts;
+use crate::reflow::reflow_paragraphs;
+use pyo3::{exceptions, pyfunction, Py, PyAny, PyErr, PyResult, Python};
+use std::collections::HashSet;
 
 /// Extracts plain text from a PDF file.
 ///
-/// This uses the pure-Rust `pdf-extract` crate. It works well for many PDFs,
-/// but for tricky CJK encodings or missing ToUnicode maps you may want to
-/// switch to a PDFium-based backend later.
-///
 /// Parameters
 /// ----------
 /// path : str
 ///     Path to the PDF file on disk.
+/// password : str, optional
+///     Password to decrypt the PDF with, if it is encrypted. Defaults to
+///     the empty string, which unlocks the common "empty user password"
+///     case.
+/// backend : str, default "auto"
+///     Which extraction engine to use: `"pure-rust"` (the `pdf-extract`
+///     crate; fast, but struggles with missing/broken ToUnicode CMaps),
+///     `"pdfium"` (Google's PDFium; slower, but handles those CJK PDFs),
+///     or `"auto"`, which runs `"pure-rust"` first and retries only the
+///     pages it leaves empty/garbled through `"pdfium"`.
+/// expand_ligatures : bool, default False
+///     If `true`, expand Unicode presentation-form ligatures (U+FB00-FB06,
+///     e.g. ﬁ→"fi") to their constituent letters before returning. See
+///     `normalize_extracted_text()`.
+/// fold_fullwidth_ascii : bool, default False
+///     If `true`, fold fullwidth Latin letters/digits to halfwidth before
+///     returning. See `normalize_extracted_text()`.
+/// reflow : bool, default False
+///     If `true`, merge soft-wrapped lines into paragraphs with
+///     `reflow_paragraphs()` before returning. Applied after ligature/
+///     fullwidth normalization.
 ///
 /// Returns
 /// -------
 /// str
 ///     Concatenated text of all pages.
 #[pyfunction]
-pub fn extract_pdf_text(path: &str) -> PyResult<String> {
-    let text = pdf_extract::extract_text(path).map_err(|e| {
-        exceptions::PyRuntimeError::new_err(format!(
-            "Failed to extract text from PDF '{}': {e}",
-            path
-        ))
-    })?;
-    Ok(text)
+#[pyo3(signature = (
+    path,
+    password=None,
+    backend="auto",
+    expand_ligatures=false,
+    fold_fullwidth_ascii=false,
+    reflow=false,
+))]
+pub fn extract_pdf_text(
+    path: &str,
+    password: Option<&str>,
+    backend: &str,
+    expand_ligatures: bool,
+    fold_fullwidth_ascii: bool,
+    reflow: bool,
+) -> PyResult<String> {
+    let text = extract_text_with_backend(BackendKind::parse(backend)?, path, password)?;
+
+    let text = normalize_extracted_text(
+        &text,
+        NormalizeOptions {
+            expand_ligatures,
+            fold_fullwidth_ascii,
+        },
+    );
+
+    Ok(if reflow {
+        reflow_paragraphs(&text, false, true, true, true, true)
+    } else {
+        text
+    })
 }
 
 /// Extracts plain text from a PDF file, split by pages.
 ///
-/// This uses the pure-Rust `pdf-extract` crate. It returns one string per page,
-/// in reading order. This is useful if you want to show a progress bar while
-/// processing each page sequentially in Python.
+/// This returns one string per page, in reading order. This is useful if
+/// you want to show a progress bar while processing each page
+/// sequentially in Python.
 ///
 /// Parameters
 /// ----------
 /// path : str
 ///     Path to the PDF file on disk.
+/// pages : str, optional
+///     A PyPDF2-style page-range spec, e.g. `"1-5,8,10-"` (1-based,
+///     inclusive, open-ended on either side). When omitted, every page
+///     is returned.
+/// password : str, optional
+///     Password to decrypt the PDF with, if it is encrypted.
+/// backend : str, default "auto"
+///     Which extraction engine to use; see `extract_pdf_text()`.
+/// expand_ligatures : bool, default False
+///     If `true`, expand Unicode presentation-form ligatures (U+FB00-FB06,
+///     e.g. ﬁ→"fi") to their constituent letters within each page.
+/// fold_fullwidth_ascii : bool, default False
+///     If `true`, fold fullwidth Latin letters/digits to halfwidth within
+///     each page.
+/// reflow : bool, default False
+///     If `true`, merge soft-wrapped lines into paragraphs with
+///     `reflow_paragraphs()` within each returned page. Applied after
+///     ligature/fullwidth normalization.
 ///
 /// Returns
 /// -------
 /// List[str]
-///     A list of page texts. `result[i]` is the text of page `i + 1`.
+///     A list of page texts, one entry per selected page, in reading order.
 #[pyfunction]
-pub fn extract_pdf_text_pages(path: &str) -> PyResult<Vec<String>> {
-    let pages = pdf_extract::extract_text_by_pages(path).map_err(|e| {
-        exceptions::PyRuntimeError::new_err(format!(
-            "Failed to extract text by pages from PDF '{}': {e}",
-            path
-        ))
-    })?;
-    Ok(pages)
+#[pyo3(signature = (
+    path,
+    pages=None,
+    password=None,
+    backend="auto",
+    expand_ligatures=false,
+    fold_fullwidth_ascii=false,
+    reflow=false,
+))]
+pub fn extract_pdf_text_pages(
+    path: &str,
+    pages: Option<&str>,
+    password: Option<&str>,
+    backend: &str,
+    expand_ligatures: bool,
+    fold_fullwidth_ascii: bool,
+    reflow: bool,
+) -> PyResult<Vec<String>> {
+    let all_pages = extract_pages_with_backend(BackendKind::parse(backend)?, path, password)?;
+
+    let pages_text: Vec<String> = match pages {
+        None => all_pages,
+        Some(spec) => {
+            let selected = parse_page_range(spec, all_pages.len())?;
+            selected
+                .into_iter()
+                .filter_map(|p| all_pages.get(p as usize - 1).cloned())
+                .collect()
+        }
+    };
+
+    let options = NormalizeOptions {
+        expand_ligatures,
+        fold_fullwidth_ascii,
+    };
+    let pages_text: Vec<String> = pages_text
+        .iter()
+        .map(|p| normalize_extracted_text(p, options))
+        .collect();
+
+    Ok(if reflow {
+        pages_text.iter().map(|p| reflow_paragraphs(p, false, true, true, true, true)).collect()
+    } else {
+        pages_text
+    })
 }
 
 /// Extracts PDF text page-by-page and reports progress to a Python callback.
 ///
-/// For PDFs where `pdf-extract` can see the page tree:
-///   - iterates real pages, including blank ones (blank → "").
-/// For PDFs where `get_pages()` returns empty:
-///   - falls back to `extract_text(path)` and calls the callback once as 1/1.
+/// Extraction itself is delegated to `backend` (see `extract_pdf_text()`),
+/// so a `"auto"` caller gets PDFium retried per-page, rather than the
+/// document falling back wholesale only when its page tree is empty.
+///
+/// `pages`, if given, restricts iteration to the selected page-range spec
+/// (see `extract_pdf_text_pages`); `total` is then the selected page count,
+/// not the document's full page count.
+///
+/// `password`, if given, decrypts an encrypted PDF before extraction (see
+/// `extract_pdf_text`).
+///
+/// `expand_ligatures`/`fold_fullwidth_ascii`, if true, clean up
+/// presentation-form ligatures and fullwidth Latin before each page is
+/// handed to the callback (see `normalize_extracted_text`). Applied
+/// before `reflow`.
+///
+/// `reflow`, if true, merges soft-wrapped lines into paragraphs with
+/// `reflow_paragraphs()` before each page is handed to the callback.
 ///
-/// callback signature: callback(page_number, total_pages, text)
+/// `classify`, if true, classifies each selected page with
+/// `classify_pdf_pages()`'s per-page logic and passes the resulting
+/// `PageInfo` as a 4th callback argument, so a caller can route only
+/// `likely_scanned` pages to an OCR pipeline instead of guessing from
+/// text length itself.
+///
+/// callback signature: callback(page_number, total_pages, text[, page_info])
 #[pyfunction]
-pub fn extract_pdf_pages_with_callback(path: &str, callback: Py<PyAny>) -> PyResult<()> {
-    use pyo3::exceptions;
-
-    let doc = match Document::load(path) {
-        Ok(d) => d,
+#[pyo3(signature = (
+    path,
+    callback,
+    pages=None,
+    password=None,
+    backend="auto",
+    expand_ligatures=false,
+    fold_fullwidth_ascii=false,
+    reflow=false,
+    classify=false,
+))]
+pub fn extract_pdf_pages_with_callback(
+    path: &str,
+    callback: Py<PyAny>,
+    pages: Option<&str>,
+    password: Option<&str>,
+    backend: &str,
+    expand_ligatures: bool,
+    fold_fullwidth_ascii: bool,
+    reflow: bool,
+    classify: bool,
+) -> PyResult<()> {
+    let all_pages = extract_pages_with_backend(BackendKind::parse(backend)?, path, password)?;
 
-        Err(e) => {
-            // Detect file-not-found specifically
-            let msg = e.to_string();
-
-            let is_not_found =
-                msg.contains("No such file")
-                    || msg.contains("cannot find the file")
-                    || msg.contains("os error 2");
+    let page_infos = if classify {
+        Some(classify_page_texts(path, password, &all_pages)?)
+    } else {
+        None
+    };
 
-            if is_not_found {
-                return Err(exceptions::PyFileNotFoundError::new_err(path.to_string()));
-            }
+    let selected_positions: Vec<usize> = match pages {
+        None => (0..all_pages.len()).collect(),
+        Some(spec) => parse_page_range(spec, all_pages.len())?
+            .into_iter()
+            .map(|p| p as usize - 1)
+            .collect(),
+    };
 
-            // All other errors are real PDF/load errors
-            return Err(exceptions::PyRuntimeError::new_err(format!(
-                "Failed to open PDF '{}': {e}",
-                path
-            )));
-        }
+    let normalize_options = NormalizeOptions {
+        expand_ligatures,
+        fold_fullwidth_ascii,
     };
 
-    let pages = doc.get_pages();
-    let total_pages = pages.len();
-
-    // fn normalize_page_text(mut s: String) -> String {
-    //     if s.contains('\r') {
-    //         s = s.replace("\r\n", "\n").replace('\r', "\n");
-    //     }
-    //     if s.trim().is_empty() {
-    //         return "\n".to_string();
-    //     }
-    //     let t = s.trim().to_string();
-    //     format!("{t}\n\n")
-    // }
-    fn normalize_page_text(mut s: String) -> String {
+    fn normalize_page_text(mut s: String, options: NormalizeOptions, reflow: bool) -> String {
         // Normalize newlines
         if s.contains('\r') {
             s = s.replace("\r\n", "\n").replace('\r', "\n");
@@ -113,6 +237,12 @@ pub fn extract_pdf_pages_with_callback(path: &str, callback: Py<PyAny>) -> PyRes
             return "\n\n".to_string();
         }
 
+        s = normalize_extracted_text(&s, options);
+
+        if reflow {
+            s = reflow_paragraphs(&s, false, true, true, true, true);
+        }
+
         // IMPORTANT: do NOT trim the page text; only trim trailing newlines
         while s.ends_with('\n') {
             s.pop();
@@ -122,57 +252,94 @@ pub fn extract_pdf_pages_with_callback(path: &str, callback: Py<PyAny>) -> PyRes
         s
     }
 
-    // Fallback: 0-page tree => single chunk
-    if total_pages == 0 {
-        eprintln!(
-            "Warning: pdf-extract reports 0 pages for '{}'; falling back to single-chunk extract_text().",
-            path
-        );
-
-        let text = pdf_extract::extract_text(path).map_err(|e| {
-            exceptions::PyRuntimeError::new_err(format!(
-                "Failed to extract text from PDF '{}': {e}",
-                path
-            ))
-        })?;
-
-        if text.trim().is_empty() {
-            return Err(exceptions::PyRuntimeError::new_err(format!(
-                "Pure-Rust pdf-extract could not extract any text from '{}'. This PDF likely requires a PDFium-based engine.",
-                path
-            )));
+    let total = selected_positions.len();
+
+    Python::attach(move |py| -> PyResult<()> {
+        for (idx, pos) in selected_positions.iter().copied().enumerate() {
+            let text = normalize_page_text(all_pages[pos].clone(), normalize_options, reflow);
+
+            // 1-based page index for callback, consistent with your PDFium ctypes
+            let page_1_based = idx + 1;
+            match &page_infos {
+                Some(infos) => {
+                    callback.call1(py, (page_1_based, total, text, infos[pos].clone()))?;
+                }
+                None => {
+                    callback.call1(py, (page_1_based, total, text))?;
+                }
+            }
         }
+        Ok(())
+    })
+}
 
-        let text = normalize_page_text(text);
+/// Parses a PyPDF2-style page-range spec into an ordered, de-duplicated
+/// list of real (1-based) page numbers.
+///
+/// Accepts comma-separated segments: a single page (`"8"`), a closed range
+/// (`"1-5"`), or a range open-ended on either side (`"10-"`, `"-5"`).
+/// Out-of-range endpoints are clamped into `[1, total_pages]` rather than
+/// rejected; a segment that becomes empty after clamping is silently
+/// dropped. Malformed tokens (non-numeric, or a range with start > end)
+/// are an error.
+pub fn parse_page_range(spec: &str, total_pages: usize) -> PyResult<Vec<u32>> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
 
-        return Python::attach(|py| {
-            callback.call1(py, (1usize, 1usize, text))?;
-            Ok(())
-        });
-    }
+    for segment in spec.split(',') {
+        let segment = segment.trim();
+        if segment.is_empty() {
+            continue;
+        }
 
-    // Normal path
-    let page_numbers: Vec<u32> = pages.keys().copied().collect();
+        let (start, end) = if let Some((a, b)) = segment.split_once('-') {
+            let a = a.trim();
+            let b = b.trim();
 
-    Python::attach(move |py| -> PyResult<()> {
-        for (idx, page_number) in page_numbers.iter().copied().enumerate() {
-            let raw = match doc.extract_text(&[page_number]) {
-                Ok(t) => t,
-                Err(e) => {
-                    eprintln!(
-                        "Warning: failed to extract text from page {} of '{}': {} — treating as blank page.",
-                        page_number, path, e
-                    );
-                    String::new()
-                }
+            let start = if a.is_empty() {
+                1
+            } else {
+                a.parse::<usize>()
+                    .map_err(|_| invalid_page_range_err(spec))?
+            };
+            let end = if b.is_empty() {
+                usize::MAX
+            } else {
+                b.parse::<usize>()
+                    .map_err(|_| invalid_page_range_err(spec))?
             };
+            (start, end)
+        } else {
+            let n = segment
+                .parse::<usize>()
+                .map_err(|_| invalid_page_range_err(spec))?;
+            (n, n)
+        };
+
+        if start == 0 || end == 0 || start > end {
+            return Err(invalid_page_range_err(spec));
+        }
 
-            let text = normalize_page_text(raw);
+        if total_pages == 0 {
+            continue;
+        }
 
-            // 1-based page index for callback, consistent with your PDFium ctypes
-            let page_1_based = idx + 1;
-            callback.call1(py, (page_1_based, total_pages, text))?;
+        let start = start.clamp(1, total_pages);
+        let end = end.clamp(1, total_pages);
+        if start > end {
+            continue;
         }
-        Ok(())
-    })
-}
\ No newline at end of file
+
+        for p in start..=end {
+            if seen.insert(p) {
+                result.push(p as u32);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn invalid_page_range_err(spec: &str) -> PyErr {
+    exceptions::PyValueError::new_err(format!("Invalid page range spec: '{}'", spec))
+}
@@ -10,7 +10,9 @@
 
 use crate::cjk_text;
 use once_cell::sync::Lazy;
+use pyo3::pyfunction;
 use smallvec::SmallVec;
+use std::borrow::Cow;
 use std::collections::HashSet;
 
 /// Broad CJK punctuation that can appear at the end of a logical unit.
@@ -178,6 +180,43 @@ pub fn is_matching_bracket(open: char, close: char) -> bool {
     BRACKET_PAIRS.iter().any(|&(o, c)| o == open && c == close)
 }
 
+/// Canonical bracket family, width- and variant-agnostic. Used by
+/// [`has_unclosed_bracket_lenient`] so that OCR width confusion (a
+/// full-width `（` closed by an ASCII `)`) doesn't register as a
+/// mismatched pair the way strict [`is_matching_bracket`] would treat it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketKind {
+    Paren,
+    Square,
+    Curly,
+    Angle,
+    CjkLenticular,
+    CjkDoubleAngle,
+    CjkTortoiseShell,
+    CjkWhiteLenticular,
+}
+
+/// Maps a bracket opener or closer, in any of its width/glyph variants,
+/// to its canonical [`BracketKind`]. `（`/`(` and `）`/`)` both map to
+/// `Paren`, `＜`/`<`/`⟨`/`〈` and their closers all map to `Angle`, and so
+/// on. Returns `None` for characters that aren't brackets at all.
+#[inline]
+pub fn canonical_bracket_kind(ch: char) -> Option<BracketKind> {
+    use BracketKind::*;
+
+    match ch {
+        '（' | '）' | '(' | ')' => Some(Paren),
+        '［' | '］' | '[' | ']' => Some(Square),
+        '｛' | '｝' | '{' | '}' => Some(Curly),
+        '＜' | '＞' | '<' | '>' | '⟨' | '⟩' | '〈' | '〉' => Some(Angle),
+        '【' | '】' => Some(CjkLenticular),
+        '《' | '》' => Some(CjkDoubleAngle),
+        '〔' | '〕' => Some(CjkTortoiseShell),
+        '〖' | '〗' => Some(CjkWhiteLenticular),
+        _ => None,
+    }
+}
+
 #[inline]
 pub fn is_strong_sentence_end(ch: char) -> bool {
     matches!(ch, '。' | '！' | '？' | '!' | '?')
@@ -286,8 +325,10 @@ pub fn last_two_non_whitespace_idx(s: &str) -> Option<((usize, char), (usize, ch
 /// NOTE: intentionally pessimistic.
 /// - Any stray closer is treated as unsafe.
 /// - Any mismatch is treated as unsafe.
+/// Both apply only while `policy.pessimistic_brackets` is set; otherwise a
+/// stray closer is skipped and a mismatched closer still pops the opener.
 #[inline]
-pub fn has_unclosed_bracket(s: &str) -> bool {
+pub fn has_unclosed_bracket(s: &str, policy: &ReflowPolicy) -> bool {
     let mut stack: SmallVec<[char; 4]> = SmallVec::new();
     let mut seen_bracket = false;
 
@@ -301,13 +342,18 @@ pub fn has_unclosed_bracket(s: &str) -> bool {
         if is_bracket_closer(ch) {
             seen_bracket = true;
 
-            // STRICT: stray closer = unsafe
+            // STRICT (default): stray closer = unsafe.
             let open = match stack.pop() {
                 Some(o) => o,
-                None => return true,
+                None => {
+                    if policy.pessimistic_brackets {
+                        return true;
+                    }
+                    continue;
+                }
             };
 
-            if !is_matching_bracket(open, ch) {
+            if !is_matching_bracket(open, ch) && policy.pessimistic_brackets {
                 return true;
             }
         }
@@ -316,13 +362,276 @@ pub fn has_unclosed_bracket(s: &str) -> bool {
     seen_bracket && !stack.is_empty()
 }
 
+/// Lenient counterpart to [`has_unclosed_bracket`], for scanned/OCR text:
+/// matches openers and closers by [`BracketKind`] (via
+/// [`canonical_bracket_kind`]) instead of exact character, so a full-width
+/// opener closed by its half-width counterpart — extremely common when
+/// OCR recovers width inconsistently — is no longer flagged as a
+/// mismatch. A stray closer or a genuine kind mismatch (e.g. `（` closed
+/// by `]`) is still treated as unsafe; only width is relaxed, not
+/// bracket type.
+#[inline]
+pub fn has_unclosed_bracket_lenient(s: &str) -> bool {
+    let mut stack: SmallVec<[BracketKind; 4]> = SmallVec::new();
+    let mut seen_bracket = false;
+
+    for ch in s.chars() {
+        if let Some(kind) = canonical_bracket_kind(ch) {
+            if is_bracket_opener(ch) {
+                seen_bracket = true;
+                stack.push(kind);
+                continue;
+            }
+
+            if is_bracket_closer(ch) {
+                seen_bracket = true;
+
+                match stack.pop() {
+                    Some(open_kind) if open_kind == kind => {}
+                    _ => return true,
+                }
+            }
+        }
+    }
+
+    seen_bracket && !stack.is_empty()
+}
+
+/// Python-facing form of [`has_unclosed_bracket_lenient`], for callers
+/// scanning scanned/OCR text directly (where strict [`has_unclosed_bracket`]
+/// would over-flag on width-inconsistent brackets).
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Candidate buffer to scan.
+///
+/// Returns
+/// -------
+/// bool
+///     `True` if `text` has an opener with no matching closer, tolerating
+///     full-width/half-width mismatches within the same bracket kind.
+#[pyfunction]
+#[pyo3(name = "has_unclosed_bracket_lenient")]
+pub fn has_unclosed_bracket_lenient_py(text: &str) -> bool {
+    has_unclosed_bracket_lenient(text)
+}
+
+/// Single-pass opener/closer stack shared by [`find_unclosed_bracket`] and
+/// [`innermost_open_bracket`], returning whatever openers are left
+/// unmatched at the end of `s` (bottom of the `SmallVec` is the outermost,
+/// top is the innermost).
+#[inline]
+fn unclosed_bracket_stack(s: &str) -> SmallVec<[(char, usize); 4]> {
+    let mut stack: SmallVec<[(char, usize); 4]> = SmallVec::new();
+
+    for (i, ch) in s.char_indices() {
+        if is_bracket_opener(ch) {
+            stack.push((ch, i));
+            continue;
+        }
+
+        if is_bracket_closer(ch) {
+            if let Some(&(open, _)) = stack.last() {
+                if is_matching_bracket(open, ch) {
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    stack
+}
+
+/// A still-open bracket found by [`find_unclosed_bracket`]: the opener
+/// itself, its byte offset in the scanned string, and how many openers
+/// were still on the stack at the end of the scan (itself included).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnclosedBracket {
+    pub open: char,
+    pub open_byte_index: usize,
+    pub depth: usize,
+}
+
+/// Like [`has_unclosed_bracket`], but reports *where* the problem is
+/// instead of just whether there is one: the **outermost** opener still
+/// open at the end of `s`, plus how deeply nested the stack was.
+///
+/// Uses its own single-pass `(char, byte_index)` stack, in which a closer
+/// that doesn't match the innermost opener is ignored (unlike
+/// [`has_unclosed_bracket`]). Callers can use `open_byte_index` to break
+/// mid-line at the opener's position rather than pessimistically
+/// refusing to flush the whole buffer.
+///
+/// Returns `None` if every opener was matched by a closer.
+pub fn find_unclosed_bracket(s: &str) -> Option<UnclosedBracket> {
+    let stack = unclosed_bracket_stack(s);
+    let (open, open_byte_index) = *stack.first()?;
+
+    Some(UnclosedBracket {
+        open,
+        open_byte_index,
+        depth: stack.len(),
+    })
+}
+
+/// Like [`find_unclosed_bracket`], but reports the **innermost** still-open
+/// opener (top of the stack) — the bracket a recovery pass would want to
+/// close first.
+pub fn innermost_open_bracket(s: &str) -> Option<(char, usize)> {
+    unclosed_bracket_stack(s).last().copied()
+}
+
+/// Python-facing form of [`find_unclosed_bracket`], flattened to a
+/// `(opener, open_byte_index, depth)` tuple so a caller doing OCR/PDF
+/// recovery can locate the outermost unclosed bracket without needing the
+/// [`UnclosedBracket`] struct.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Candidate buffer (e.g. a not-yet-flushed reflow buffer) to scan.
+///
+/// Returns
+/// -------
+/// Optional[Tuple[str, int, int]]
+///     `(opener, open_byte_index, depth)`, or `None` if every opener in
+///     `text` was matched by a closer.
+#[pyfunction]
+#[pyo3(name = "find_unclosed_bracket")]
+pub fn find_unclosed_bracket_py(text: &str) -> Option<(char, usize, usize)> {
+    find_unclosed_bracket(text).map(|b| (b.open, b.open_byte_index, b.depth))
+}
+
+/// Python-facing form of [`innermost_open_bracket`]: the innermost
+/// still-open opener in `text` — the bracket a recovery pass should try to
+/// close first — or `None` if brackets in `text` are balanced.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Candidate buffer to scan.
+///
+/// Returns
+/// -------
+/// Optional[Tuple[str, int]]
+///     `(opener, open_byte_index)`, or `None` if brackets are balanced.
+#[pyfunction]
+#[pyo3(name = "innermost_open_bracket")]
+pub fn innermost_open_bracket_py(text: &str) -> Option<(char, usize)> {
+    innermost_open_bracket(text)
+}
+
+/// Joins two physically-adjacent reflow lines with whatever spacing rule
+/// fits the seam between them, centralizing the "smartly fix up
+/// whitespace" logic that used to live inline in the reflow loop. Fixes
+/// the common "CJK文字 split" → "CJK文字split" space-injection bug.
+///
+/// Rules, checked in order:
+/// 1. Soft hyphenation: `prev` ends with an ASCII `-` preceded by a Latin
+///    letter, and `next` starts with a Latin letter — drop the hyphen and
+///    concatenate with no space (`"exam-"` + `"ple"` → `"example"`).
+/// 2. CJK on both sides of the seam ([`cjk_text::is_cjk_bmp`] on the last
+///    non-whitespace char of `prev` and the first non-space char of
+///    `next`) — concatenate with no inserted space.
+/// 3. Latin word characters on both sides — insert exactly one ASCII
+///    space.
+/// 4. Otherwise — concatenate verbatim; `next`'s leading wrap-boundary
+///    whitespace is trimmed, `prev` is used as-is.
+pub fn join_reflow_lines(prev: &str, next: &str) -> String {
+    let next = next.trim_start();
+    let mut joined = String::with_capacity(prev.len() + next.len() + 1);
+
+    let prev_last = last_non_whitespace(prev);
+    let next_first = next.chars().next();
+
+    // 1) Soft hyphenation.
+    if prev.ends_with('-')
+        && prev
+            .chars()
+            .rev()
+            .nth(1)
+            .is_some_and(|c| c.is_ascii_alphabetic())
+        && next_first.is_some_and(|c| c.is_ascii_alphabetic())
+    {
+        joined.push_str(&prev[..prev.len() - 1]);
+        joined.push_str(next);
+        return joined;
+    }
+
+    // 2) CJK-to-CJK seam: no inserted space.
+    if prev_last.is_some_and(cjk_text::is_cjk_bmp) && next_first.is_some_and(cjk_text::is_cjk_bmp) {
+        joined.push_str(prev);
+        joined.push_str(next);
+        return joined;
+    }
+
+    // 3) Latin-word-to-Latin-word seam: exactly one space.
+    if prev_last.is_some_and(|c| c.is_ascii_alphanumeric())
+        && next_first.is_some_and(|c| c.is_ascii_alphanumeric())
+    {
+        joined.push_str(prev);
+        joined.push(' ');
+        joined.push_str(next);
+        return joined;
+    }
+
+    // 4) Otherwise: concatenate verbatim.
+    joined.push_str(prev);
+    joined.push_str(next);
+    joined
+}
+
+/// Tunable knobs for [`ends_with_sentence_boundary`] and
+/// [`has_unclosed_bracket`], analogous to rust-analyzer's
+/// `JoinLinesConfig`: a plain struct of booleans threaded into the join
+/// logic, so the reflow loop can be tuned per corpus instead of forking
+/// the heuristics. A clean EPUB-derived PDF may want strict strong-enders
+/// only; a noisy scan wants the OCR ASCII `.`/`:` rules and the
+/// full-width-colon weak boundary turned on.
+///
+/// [`Default`] reproduces the behavior these functions had before this
+/// struct existed, i.e. every rule enabled.
+#[derive(Debug, Clone, Copy)]
+pub struct ReflowPolicy {
+    /// Treat a trailing full-width/half-width colon as a weak sentence
+    /// boundary (common: "他说：" then dialog on the next line).
+    pub treat_colon_as_boundary: bool,
+    /// Treat a trailing ellipsis (`…`, `......`, `...`, `..`) as a weak
+    /// sentence boundary.
+    pub treat_ellipsis_as_boundary: bool,
+    /// Enable the OCR-artifact rules that treat a bare ASCII `.`/`:` as a
+    /// sentence ender in mostly-CJK text, both at line end and just
+    /// before a run of dialog/bracket closers.
+    pub enable_ocr_ascii_punct: bool,
+    /// Let [`is_allowed_postfix_closer`] (e.g. `）`/`)`) count alongside
+    /// dialog closers when looking for a strong-end-then-closer boundary.
+    pub allow_postfix_closer: bool,
+    /// In [`has_unclosed_bracket`], treat a stray closer or a
+    /// type-mismatched pair as unsafe (unclosed) rather than ignoring it.
+    pub pessimistic_brackets: bool,
+}
+
+impl Default for ReflowPolicy {
+    fn default() -> Self {
+        Self {
+            treat_colon_as_boundary: true,
+            treat_ellipsis_as_boundary: true,
+            enable_ocr_ascii_punct: true,
+            allow_postfix_closer: true,
+            pessimistic_brackets: true,
+        }
+    }
+}
+
 // ------ Sentence Boundary start ------ //
 
 /// Level-2 normalized sentence boundary detection.
 ///
 /// Includes OCR artifacts (ASCII '.' / ':'), but **does not** treat a bare
 /// bracket closer as a sentence boundary (that causes false flushes like "（亦作肥）").
-pub fn ends_with_sentence_boundary(s: &str) -> bool {
+/// Which rules are active is controlled by `policy`.
+pub fn ends_with_sentence_boundary(s: &str, policy: &ReflowPolicy) -> bool {
     if s.trim().is_empty() {
         return false;
     }
@@ -339,29 +648,35 @@ pub fn ends_with_sentence_boundary(s: &str) -> bool {
     }
 
     // 2) OCR '.' / ':' at line end (mostly-CJK).
-    if (last == '.' || last == ':') && is_ocr_cjk_ascii_punct_at_line_end(s, last_i) {
+    if policy.enable_ocr_ascii_punct
+        && (last == '.' || last == ':')
+        && is_ocr_cjk_ascii_punct_at_line_end(s, last_i)
+    {
         return true;
     }
 
     // 3) Quote closers + Allowed postfix closer after strong end,
     //    plus OCR artifact `.“”` / `.」` / `.）`.
-    if is_dialog_closer(last) || is_allowed_postfix_closer(last) {
+    if is_dialog_closer(last) || (policy.allow_postfix_closer && is_allowed_postfix_closer(last)) {
         if is_strong_sentence_end(prev) {
             return true;
         }
 
-        if prev == '.' && is_ocr_cjk_ascii_punct_before_closers(s, prev_i) {
+        if policy.enable_ocr_ascii_punct
+            && prev == '.'
+            && is_ocr_cjk_ascii_punct_before_closers(s, prev_i)
+        {
             return true;
         }
     }
 
     // 4) Full-width colon as a weak boundary (common: "他说：" then dialog next line)
-    if is_colon_like(last) && cjk_text::is_mostly_cjk(s) {
+    if policy.treat_colon_as_boundary && is_colon_like(last) && cjk_text::is_mostly_cjk(s) {
         return true;
     }
 
     // 5) Ellipsis as weak boundary.
-    if ends_with_ellipsis(s) {
+    if policy.treat_ellipsis_as_boundary && ends_with_ellipsis(s) {
         return true;
     }
 
@@ -500,3 +815,188 @@ pub fn begins_with_dialog_opener(s: &str) -> bool {
         .next()
         .is_some_and(|ch| is_dialog_opener(ch))
 }
+
+// ------ Confusable Normalization start ------ //
+
+/// Doubled-ASCII-quote sequences that OCR sometimes emits in place of a
+/// CJK corner bracket, tried before the per-char table below since each
+/// spans two input chars.
+const MULTI_CHAR_CONFUSABLES: &[(&str, &str)] = &[("``", "「"), ("''", "」")];
+
+/// Sorted by codepoint `(source, canonical)` table of one-to-one
+/// confusable/homoglyph punctuation, in the spirit of rustc's
+/// `UNICODE_ARRAY` confusables table for lexer diagnostics: halfwidth CJK
+/// punctuation and a few comma/dot homoglyphs borrowed from other scripts,
+/// normalized to the CJK punctuation the boundary detectors above already
+/// recognize. Must stay sorted by `source` — [`normalize_single_confusable`]
+/// binary-searches it.
+const CONFUSABLE_CHARS: &[(u32, char)] = &[
+    (0x00B7, '・'), // MIDDLE DOT
+    (0x055D, '，'), // ARMENIAN COMMA
+    (0x201A, '，'), // SINGLE LOW-9 QUOTATION MARK
+    (0xFF61, '。'), // HALFWIDTH IDEOGRAPHIC FULL STOP
+    (0xFF64, '、'), // HALFWIDTH IDEOGRAPHIC COMMA
+];
+
+/// Maps a single confusable/homoglyph char to its canonical form: fullwidth
+/// Latin letters/digits (pure OCR width artifacts) collapse to halfwidth,
+/// dash/tilde lookalikes unify to the forms [`CJK_PUNCT_END`] and
+/// `is_box_drawing_line` already recognize, and anything else is looked up
+/// in [`CONFUSABLE_CHARS`]. Characters with an established CJK punctuation
+/// role elsewhere in this module (，。！？：；（）etc.) are left untouched.
+pub(crate) fn normalize_single_confusable(ch: char) -> char {
+    match ch {
+        'Ａ'..='Ｚ' | 'ａ'..='ｚ' | '０'..='９' => {
+            char::from_u32(ch as u32 - 0xFEE0).unwrap_or(ch)
+        }
+        '‐' | '‑' | '‒' | '–' | '―' | '−' => '—',
+        '~' | '∼' | '˜' => '～',
+        _ => CONFUSABLE_CHARS
+            .binary_search_by_key(&(ch as u32), |&(src, _)| src)
+            .map(|idx| CONFUSABLE_CHARS[idx].1)
+            .unwrap_or(ch),
+    }
+}
+
+/// Confusable/homoglyph normalization pre-pass for OCR/PDF-extracted text,
+/// meant to run before boundary checks like [`ends_with_sentence_boundary`]
+/// and [`ends_with_cjk_bracket_boundary`] so those checks see canonical
+/// punctuation instead of look-alikes: an Armenian comma or a low-9
+/// quotation mark standing in for `，`, halfwidth CJK punctuation, or a
+/// doubled ASCII quote standing in for a CJK corner bracket. A Latin full
+/// stop is left unchanged. This exact function is what [`crate::reflow::reflow_paragraphs`]
+/// runs under its `normalize_confusables` flag.
+///
+/// `crate::reflow_helper`'s dialog-aware pipeline does *not* call this
+/// function directly — it has its own per-line `normalize_punctuation_line`,
+/// which needs [`DialogState`](crate::reflow_helper) open/close toggling to
+/// tell an opening ASCII `"`/`'` from a closing one (something a blind
+/// table lookup can't do) — but it's built on the same
+/// [`normalize_single_confusable`] table plus the same `` ` ` ``/`''`
+/// pairing this function does, applied before that line's
+/// `DialogState::update`.
+///
+/// Returns `Cow::Borrowed` untouched when nothing in `s` needs replacing,
+/// so callers on the hot reflow path pay no allocation on already-clean
+/// text. Callers who need byte-for-byte fidelity (e.g. diffing extracted
+/// text against its source) should simply not call this at all.
+pub fn normalize_confusables(s: &str) -> Cow<str> {
+    let needs_multi = MULTI_CHAR_CONFUSABLES
+        .iter()
+        .any(|&(from, _)| s.contains(from));
+    let needs_single = s.chars().any(|ch| normalize_single_confusable(ch) != ch);
+
+    if !needs_multi && !needs_single {
+        return Cow::Borrowed(s);
+    }
+
+    let mut owned = s.to_string();
+    for &(from, to) in MULTI_CHAR_CONFUSABLES {
+        if owned.contains(from) {
+            owned = owned.replace(from, to);
+        }
+    }
+
+    Cow::Owned(owned.chars().map(normalize_single_confusable).collect())
+}
+
+// ------ Confusable Normalization end ------ //
+
+// ------ Control-Char Sanitization start ------ //
+
+/// True for invisible bidi override/isolate, zero-width, BOM, and soft
+/// hyphen codepoints that PDF/web-scraped text can carry: bidi overrides/
+/// isolates (U+202A–U+202E, U+2066–U+2069), zero-width space/joiners
+/// (U+200B–U+200D), the BOM (U+FEFF), and the soft hyphen (U+00AD). None
+/// of these are whitespace or CJK, so left in place they silently corrupt
+/// [`find_last_non_whitespace_char_index`], `cjk_text::is_mostly_cjk`, and
+/// `DialogState`'s counters (see `crate::reflow_helper`) — and the bidi
+/// codepoints can visually reorder glyphs on screen without changing a
+/// single byte on disk ("Trojan Source"-style).
+fn is_invisible_control_char(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' | '\u{200B}'..='\u{200D}' | '\u{FEFF}' | '\u{00AD}'
+    )
+}
+
+/// Whether [`sanitize_control_chars`] silently strips the codepoints it
+/// finds, or leaves `s` untouched and only reports where they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCharPolicy {
+    /// Remove the codepoints and return the cleaned text.
+    Strip,
+    /// Leave `s` untouched; the caller decides what to do with the
+    /// reported positions (e.g. reject obviously adversarial input).
+    Flag,
+}
+
+/// Scans `s` for invisible bidi/zero-width/BOM/soft-hyphen control
+/// codepoints (see [`is_invisible_control_char`]), analogous to rustc's
+/// `contains_text_flow_control_chars` check for source files. Returns the
+/// text (cleaned, under [`ControlCharPolicy::Strip`], or unchanged under
+/// [`ControlCharPolicy::Flag`]) alongside the byte offset and value of
+/// every matching codepoint found — into the *original* `s` in both
+/// cases — so a caller can log suspicious input instead of it silently
+/// passing through.
+///
+/// Returns `Cow::Borrowed` and an empty `Vec` when `s` has none of these
+/// codepoints, so the hot reflow path pays no allocation on clean text.
+pub fn sanitize_control_chars(
+    s: &str,
+    policy: ControlCharPolicy,
+) -> (Cow<str>, Vec<(usize, char)>) {
+    let found: Vec<(usize, char)> = s
+        .char_indices()
+        .filter(|&(_, ch)| is_invisible_control_char(ch))
+        .collect();
+
+    if found.is_empty() {
+        return (Cow::Borrowed(s), found);
+    }
+
+    match policy {
+        ControlCharPolicy::Flag => (Cow::Borrowed(s), found),
+        ControlCharPolicy::Strip => {
+            let cleaned: String = s.chars().filter(|&ch| !is_invisible_control_char(ch)).collect();
+            (Cow::Owned(cleaned), found)
+        }
+    }
+}
+
+// ------ Control-Char Sanitization end ------ //
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_reflow_lines_soft_hyphenation() {
+        assert_eq!(join_reflow_lines("hyphen-", "ated word"), "hyphenated word");
+    }
+
+    #[test]
+    fn test_join_reflow_lines_cjk_to_cjk_no_space() {
+        assert_eq!(join_reflow_lines("正文未完", "续写内容"), "正文未完续写内容");
+    }
+
+    #[test]
+    fn test_join_reflow_lines_latin_word_gets_one_space() {
+        assert_eq!(join_reflow_lines("hello", "world"), "hello world");
+    }
+
+    #[test]
+    fn test_sanitize_control_chars_strips_invisible_chars() {
+        let (cleaned, found) =
+            sanitize_control_chars("a\u{200B}b", ControlCharPolicy::Strip);
+        assert_eq!(cleaned, "ab");
+        assert_eq!(found, vec![(1, '\u{200B}')]);
+    }
+
+    #[test]
+    fn test_sanitize_control_chars_borrows_when_clean() {
+        let (cleaned, found) = sanitize_control_chars("plain text", ControlCharPolicy::Strip);
+        assert!(matches!(cleaned, Cow::Borrowed(_)));
+        assert!(found.is_empty());
+    }
+}
@@ -0,0 +1,181 @@
+//! Reference / bibliography block extraction from reflowed CJK text.
+//!
+//! Locates the heading line that marks the start of a reference list
+//! (参考文献/參考文獻/引用/References) and segments everything after it
+//! into individual entries by leading enumerator: bracketed numbers
+//! (`[1]`), dotted numbers (`1.`), or full-width/CJK serial markers
+//! (`１、`, `一、`). Soft-wrapped continuation lines are re-joined the
+//! same way the reflow buffer does.
+
+use pyo3::pyfunction;
+
+const HEADING_MARKERS: &[&str] = &["参考文献", "參考文獻", "引用", "References"];
+
+const CJK_NUMERAL_MARKERS: &[char] = &['一', '二', '三', '四', '五', '六', '七', '八', '九', '十'];
+
+/// Closing brackets that, once seen at the end of an entry, mark it as
+/// complete: a following line without a recognized enumerator starts a
+/// new entry rather than continuing the previous one. This handles
+/// sloppy page-range citations like "...(2020), 3-12." with no leading
+/// "[n]" of their own.
+const TERMINATING_BRACKETS: &[char] = &[')', '）', '】', '》', '」', '』', '］'];
+
+/// Extract a reference/bibliography section out of reflowed text.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Reflowed text (usually the output of `reflow_cjk_paragraphs()`).
+///
+/// Returns
+/// -------
+/// List[str]
+///     One trimmed string per reference entry, in document order. Empty
+///     if no reference-section heading is found.
+#[pyfunction]
+pub fn extract_references(text: &str) -> Vec<String> {
+    let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut lines = normalized.split('\n');
+
+    // Scan for the section heading, reusing the same indentation-probe
+    // stripping the reflow loop uses before classifying a line.
+    if !lines.by_ref().any(is_reference_heading) {
+        return Vec::new();
+    }
+
+    let mut entries: Vec<String> = Vec::new();
+    let mut prev_ends_terminating = false;
+
+    for raw_line in lines {
+        let stripped = raw_line
+            .trim_end()
+            .trim_start_matches(|ch| ch == ' ' || ch == '\u{3000}');
+        let line = stripped.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(rest) = strip_enumerator(line) {
+            entries.push(rest.trim().to_string());
+        } else if prev_ends_terminating || entries.is_empty() {
+            entries.push(line.to_string());
+        } else {
+            let last = entries.last_mut().expect("entries checked non-empty above");
+            append_continuation(last, line);
+        }
+
+        prev_ends_terminating = line
+            .chars()
+            .last()
+            .is_some_and(|c| TERMINATING_BRACKETS.contains(&c));
+    }
+
+    entries
+}
+
+/// True if `raw_line`, after stripping the same left indentation the
+/// reflow loop strips before probing for headings, is exactly one of the
+/// recognized reference-section markers (ASCII match is case-insensitive,
+/// so "references" / "REFERENCES" also match).
+fn is_reference_heading(raw_line: &str) -> bool {
+    let stripped = raw_line
+        .trim_end()
+        .trim_start_matches(|ch| ch == ' ' || ch == '\u{3000}');
+    let line = stripped.trim_end_matches(['：', ':']).trim();
+    HEADING_MARKERS
+        .iter()
+        .any(|&marker| line.eq_ignore_ascii_case(marker))
+}
+
+/// If `line` begins with a recognized leading enumerator, returns the
+/// remainder of the line after it (trimmed of the separator itself).
+fn strip_enumerator(line: &str) -> Option<&str> {
+    strip_bracketed_number(line)
+        .or_else(|| strip_dotted_number(line))
+        .or_else(|| strip_fullwidth_marker(line))
+}
+
+/// `[12] ...` / `［12］...`
+fn strip_bracketed_number(line: &str) -> Option<&str> {
+    let mut chars = line.char_indices();
+    let (_, open) = chars.next()?;
+    if open != '[' && open != '［' {
+        return None;
+    }
+    let close = if open == '[' { ']' } else { '］' };
+
+    let mut saw_digit = false;
+    for (idx, ch) in chars {
+        if ch.is_ascii_digit() {
+            saw_digit = true;
+            continue;
+        }
+        if saw_digit && ch == close {
+            return Some(&line[idx + ch.len_utf8()..]);
+        }
+        break;
+    }
+    None
+}
+
+/// `1. ...` / `12. ...` — ASCII digits followed by a dot and whitespace
+/// (or end of line, for a bare "12." continuation marker).
+fn strip_dotted_number(line: &str) -> Option<&str> {
+    let bytes = line.as_bytes();
+    let mut i = 0usize;
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i == 0 || i >= bytes.len() || bytes[i] != b'.' {
+        return None;
+    }
+
+    let rest = &line[i + 1..];
+    if rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace()) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// Full-width digit runs (`１２、`, `３．`) or a bare CJK numeral marker
+/// (`一、`, `十、`).
+fn strip_fullwidth_marker(line: &str) -> Option<&str> {
+    let mut chars = line.chars();
+    let first = chars.next()?;
+
+    if CJK_NUMERAL_MARKERS.contains(&first) {
+        return chars.as_str().strip_prefix('、');
+    }
+
+    if !is_fullwidth_digit(first) {
+        return None;
+    }
+    let mut rest = chars.as_str();
+    while let Some(c) = rest.chars().next().filter(|&c| is_fullwidth_digit(c)) {
+        rest = &rest[c.len_utf8()..];
+    }
+
+    rest.strip_prefix('、').or_else(|| rest.strip_prefix('．'))
+}
+
+#[inline]
+fn is_fullwidth_digit(ch: char) -> bool {
+    ('０'..='９').contains(&ch)
+}
+
+/// Appends a soft-wrapped continuation line to `buffer`, inserting a
+/// space only between two ASCII alphanumeric runs (CJK text is joined
+/// directly, the same way the reflow buffer concatenates wrapped lines).
+fn append_continuation(buffer: &mut String, line: &str) {
+    let need_space = buffer
+        .chars()
+        .last()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+        && line.starts_with(|c: char| c.is_ascii_alphanumeric());
+
+    if need_space {
+        buffer.push(' ');
+    }
+    buffer.push_str(line);
+}
@@ -0,0 +1,136 @@
+//! Line-reflow for raw `pdf-extract` output, built directly on the
+//! boundary detectors in [`crate::punct_sets`].
+//!
+//! Unlike [`crate::reflow_cjk_paragraphs`] (which also classifies headings,
+//! dialog, and metadata), this is the minimal merge-only pass: it exists so
+//! callers of `extract_pdf_text*` can get paragraph-shaped text without
+//! paying for the full heuristic pipeline, and so that pipeline's own
+//! boundary logic has one source of truth.
+
+use crate::punct_sets::{
+    ends_with_cjk_bracket_boundary, ends_with_sentence_boundary, join_reflow_lines, ReflowPolicy,
+};
+use pyo3::pyfunction;
+use std::borrow::Cow;
+
+/// Above this input size, skip the unconditional `\r\n`/`\r` normalization
+/// copy when `text` has no `\r` bytes at all — `memchr` finds that out in
+/// one pass over the raw bytes, cheaper than cloning the whole document
+/// just to discover there was nothing to replace. Below the threshold the
+/// clone is cheap enough that the extra scan isn't worth it.
+const LARGE_DOCUMENT_THRESHOLD_BYTES: usize = 256 * 1024;
+
+/// Merges soft-wrapped lines from raw PDF-extracted text into paragraphs.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Raw text (usually from `extract_pdf_text()`).
+///
+/// normalize_confusables : bool, default False
+///     If `true`, run [`crate::punct_sets::normalize_confusables`] on each
+///     line before testing it for a boundary, so OCR/PDF-extraction
+///     look-alikes (an Armenian comma or low-9 quotation mark where `，`
+///     belongs, halfwidth CJK punctuation, doubled ASCII quotes for a
+///     CJK corner bracket; a Latin full stop is left unchanged) don't defeat `ends_with_sentence_boundary` /
+///     `ends_with_cjk_bracket_boundary`. Leave `false` (the default) for
+///     byte-faithful output.
+/// treat_colon_as_boundary : bool, default True
+/// treat_ellipsis_as_boundary : bool, default True
+/// enable_ocr_ascii_punct : bool, default True
+/// allow_postfix_closer : bool, default True
+///     [`ReflowPolicy`] fields threaded into [`ends_with_sentence_boundary`],
+///     so the merge loop can be tuned per document (see `ReflowPolicy` for
+///     what each controls) instead of always running every rule.
+///
+/// Returns
+/// -------
+/// str
+///     Reflowed text, with genuinely blank lines preserved as paragraph
+///     separators.
+///
+/// Algorithm
+/// ---------
+/// Lines are merged one at a time: a line is kept as its own paragraph
+/// break only once it ends with a sentence boundary
+/// ([`ends_with_sentence_boundary`]) or a balanced CJK bracket boundary
+/// like "（完）" ([`ends_with_cjk_bracket_boundary`]); otherwise it is
+/// joined with the next line via [`join_reflow_lines`], which picks the
+/// CJK-concatenation, de-hyphenation, or ASCII-word-join spacing rule
+/// based on the characters at the seam.
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    normalize_confusables=false,
+    treat_colon_as_boundary=true,
+    treat_ellipsis_as_boundary=true,
+    enable_ocr_ascii_punct=true,
+    allow_postfix_closer=true,
+))]
+pub fn reflow_paragraphs(
+    text: &str,
+    normalize_confusables: bool,
+    treat_colon_as_boundary: bool,
+    treat_ellipsis_as_boundary: bool,
+    enable_ocr_ascii_punct: bool,
+    allow_postfix_closer: bool,
+) -> String {
+    let policy = ReflowPolicy {
+        treat_colon_as_boundary,
+        treat_ellipsis_as_boundary,
+        enable_ocr_ascii_punct,
+        allow_postfix_closer,
+        ..ReflowPolicy::default()
+    };
+    let normalized: Cow<str> = if text.len() > LARGE_DOCUMENT_THRESHOLD_BYTES
+        && memchr::memchr(b'\r', text.as_bytes()).is_none()
+    {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    };
+    let lines = normalized.split('\n');
+
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut buffer = String::new();
+
+    for line in lines {
+        let (sanitized, _removed_control_chars) = crate::punct_sets::sanitize_control_chars(
+            line,
+            crate::punct_sets::ControlCharPolicy::Strip,
+        );
+        let line_end_trimmed = sanitized.trim_end();
+
+        let trimmed_owned;
+        let trimmed: &str = if normalize_confusables {
+            trimmed_owned = crate::punct_sets::normalize_confusables(line_end_trimmed).into_owned();
+            &trimmed_owned
+        } else {
+            line_end_trimmed
+        };
+
+        if trimmed.trim().is_empty() {
+            if !buffer.is_empty() {
+                paragraphs.push(std::mem::take(&mut buffer));
+            }
+            paragraphs.push(String::new());
+            continue;
+        }
+
+        if buffer.is_empty() {
+            buffer.push_str(trimmed);
+        } else {
+            buffer = join_reflow_lines(&buffer, trimmed);
+        }
+
+        if ends_with_sentence_boundary(&buffer, &policy) || ends_with_cjk_bracket_boundary(&buffer) {
+            paragraphs.push(std::mem::take(&mut buffer));
+        }
+    }
+
+    if !buffer.is_empty() {
+        paragraphs.push(buffer);
+    }
+
+    paragraphs.join("\n")
+}
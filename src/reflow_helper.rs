@@ -5,51 +5,179 @@
 //! - CLI (opencc-rs PDF / office / etc.)
 
 use once_cell::sync::Lazy;
-use pyo3::{pyfunction, PyResult};
-use std::collections::HashSet;
+use pyo3::types::PyDict;
+use pyo3::{pyfunction, Py, PyResult, Python};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// Above this input size, skip the unconditional `\r\n`/`\r` normalization
+/// copy when there are no `\r` bytes in `text` to begin with — `memchr`
+/// finds that out in one pass over the raw bytes, cheaper than cloning an
+/// entire book-length document just to discover there was nothing to
+/// replace. Below the threshold the clone is cheap enough that the extra
+/// scan isn't worth it.
+const LARGE_DOCUMENT_THRESHOLD_BYTES: usize = 256 * 1024;
+
+/// Normalizes `\r\n`/`\r` line endings to `\n`, borrowing `text` as-is
+/// when it's large and already has none (see [`LARGE_DOCUMENT_THRESHOLD_BYTES`]).
+fn normalize_line_endings(text: &str) -> Cow<str> {
+    if text.len() > LARGE_DOCUMENT_THRESHOLD_BYTES && memchr::memchr(b'\r', text.as_bytes()).is_none()
+    {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    }
+}
 
 // ---------------------------------------------------------------------------
 // CJK PDF Reflow Engine (Rust implementation for opencc_pyo3)
 // ---------------------------------------------------------------------------
 
-/// Reflow CJK paragraphs from PDF-extracted text.
-///
-/// This merges artificial line breaks while preserving paragraphs,
-/// headings, chapter lines, and dialog structure.
-///
-/// Parameters
-/// ----------
-/// text : &str
-///     Raw text (usually from `extract_pdf_text()`).
-/// add_pdf_page_header : bool
-///     If `false`, try to skip page-break-like blank lines that are not
-///     preceded by CJK punctuation. If `true`, keep those gaps.
-/// compact : bool
-///     If `true`, paragraphs are joined with a single newline ("p1\\np2").
-///     If `false`, paragraphs are separated by a blank line ("p1\\n\\np2").
+/// User-tunable overrides for the structural heuristics in
+/// [`reflow_cjk_paragraphs`], for material that doesn't follow novel-style
+/// conventions (technical manuals, scripts, web serials with their own
+/// chapter markers).
 ///
-/// Returns
-/// -------
-/// String
-///     Reflowed text.
-///
-#[pyfunction]
-pub fn reflow_cjk_paragraphs(
+/// `custom_patterns` are matched as plain substrings rather than compiled
+/// regexes — this crate has no regex dependency, and (as rustc's own test
+/// filter did when it dropped its regex dependency) substring matching
+/// covers the overwhelming majority of real patterns with none of the
+/// compile cost.
+#[derive(Debug, Clone, Default)]
+pub struct ReflowConfig {
+    /// Extra heading keywords, checked the same way as the built-in
+    /// `HEADING_KEYWORDS` (line must start with one of them).
+    pub extra_heading_keywords: Vec<String>,
+    /// Extra chapter-marker characters, checked alongside the built-in
+    /// `CHAPTER_MARKERS` set (e.g. '章', '回').
+    pub extra_chapter_markers: Vec<char>,
+    /// Extra metadata keys, checked alongside the built-in `METADATA_KEYS`
+    /// set for "Key: value" line detection.
+    pub extra_metadata_keys: Vec<String>,
+    /// Substrings that, if present anywhere in a candidate line, force it
+    /// to be treated as a heading.
+    pub custom_patterns: Vec<String>,
+}
+
+impl ReflowConfig {
+    fn is_chapter_marker(&self, ch: char) -> bool {
+        CHAPTER_MARKERS.contains(&ch) || self.extra_chapter_markers.contains(&ch)
+    }
+
+    fn matches_custom_pattern(&self, s: &str) -> bool {
+        self.custom_patterns
+            .iter()
+            .any(|p| !p.is_empty() && s.contains(p.as_str()))
+    }
+}
+
+/// Structural classification of a single [`ReflowSegment`], covering
+/// every shape the reflow engine already distinguishes internally:
+/// heading vs. metadata vs. dialog vs. page-marker vs. body paragraph vs.
+/// box-drawing divider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// A line recognized by `is_title_heading_line` (e.g. "第一章 ...").
+    TitleHeading,
+    /// A short standalone line recognized by `is_heading_like`.
+    ShortHeading,
+    /// A "Key: value" line recognized by `is_metadata_line`.
+    Metadata,
+    /// A page-break artifact recognized by `is_page_marker`.
+    PageMarker,
+    /// A box-drawing / `----` / `****` divider line.
+    Divider,
+    /// An ordinary body paragraph.
+    Paragraph,
+    /// A paragraph whose first line opens with a dialog quote, per
+    /// `is_dialog_start`.
+    Dialog,
+}
+
+impl SegmentKind {
+    /// Stable lowercase tag used for the Python-facing `kind` field and
+    /// for [`segments_to_json`].
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SegmentKind::TitleHeading => "title_heading",
+            SegmentKind::ShortHeading => "short_heading",
+            SegmentKind::Metadata => "metadata",
+            SegmentKind::PageMarker => "page_marker",
+            SegmentKind::Divider => "divider",
+            SegmentKind::Paragraph => "paragraph",
+            SegmentKind::Dialog => "dialog",
+        }
+    }
+}
+
+/// One classified unit of [`reflow_cjk_segments`]: a segment's text
+/// paired with its structural [`SegmentKind`]. `reflow_cjk_paragraphs` is
+/// a thin wrapper that renders a `Vec<ReflowSegment>` back to a single
+/// joined string.
+#[derive(Debug, Clone)]
+pub struct ReflowSegment {
+    pub kind: SegmentKind,
+    pub text: String,
+}
+
+/// One normalized bibliographic field collected by [`extract_metadata`].
+/// `key` is the canonical field name — merging the Traditional/Simplified
+/// and phrasing variants tracked in `METADATA_KEYS` (e.g. 書名/书名,
+/// 譯者/译者) into a single name, per [`canonical_metadata_key`] — and
+/// `value` is the trimmed text after the separator.
+#[derive(Debug, Clone)]
+pub struct MetadataField {
+    pub key: String,
+    pub value: String,
+}
+
+/// Front-matter serialization format for [`render_front_matter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    /// `---`-fenced block (Jekyll/Hugo style).
+    Yaml,
+    /// `+++`-fenced block.
+    Toml,
+}
+
+/// Picks the [`SegmentKind`] for a paragraph buffer that is about to
+/// start accumulating at `line_text`: `Dialog` if the line opens with a
+/// dialog quote (per `is_dialog_start`), `Paragraph` otherwise.
+fn paragraph_kind_for(line_text: &str) -> SegmentKind {
+    if is_dialog_start(line_text) {
+        SegmentKind::Dialog
+    } else {
+        SegmentKind::Paragraph
+    }
+}
+
+/// Core reflow engine shared by [`reflow_cjk_paragraphs`] and
+/// [`reflow_cjk_segments`]: merges artificial line breaks while
+/// preserving paragraphs, headings, chapter lines, and dialog structure,
+/// returning the classified segments rather than a joined string.
+fn reflow_cjk_segments_impl(
     text: &str,
     add_pdf_page_header: bool,
-    compact: bool,
-) -> PyResult<String> {
+    wrap_width: Option<usize>,
+    config: &ReflowConfig,
+    normalize_punctuation: bool,
+    policy: &crate::punct_sets::ReflowPolicy,
+) -> Vec<ReflowSegment> {
     // If the whole text is whitespace, return as-is.
     if text.chars().all(|c| c.is_whitespace()) {
-        return Ok(text.to_owned());
+        return vec![ReflowSegment {
+            kind: SegmentKind::Paragraph,
+            text: text.to_owned(),
+        }];
     }
 
     // Normalize line endings
-    let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
+    let normalized = normalize_line_endings(text);
     let lines = normalized.split('\n');
 
-    let mut segments: Vec<String> = Vec::new();
+    let mut segments: Vec<ReflowSegment> = Vec::new();
     let mut buffer = String::new();
+    let mut buffer_kind = SegmentKind::Paragraph;
     let mut dialog_state = DialogState::new();
 
     for raw_line in lines {
@@ -57,6 +185,16 @@ pub fn reflow_cjk_paragraphs(
         let trimmed_end = raw_line.trim_end();
         let stripped_visual = strip_halfwidth_indent_keep_fullwidth(trimmed_end);
 
+        // 1.05) Confusable-punctuation normalization, run before every other
+        // check so headings/dialog/metadata detection see canonical forms.
+        let normalized_owned;
+        let stripped_visual: &str = if normalize_punctuation {
+            normalized_owned = normalize_punctuation_line(stripped_visual, &mut dialog_state);
+            &normalized_owned
+        } else {
+            stripped_visual
+        };
+
         // 1.1) Logical probe for heading detection (no left indent)
         let probe = stripped_visual.trim_start_matches(|ch| ch == ' ' || ch == '\u{3000}');
 
@@ -64,10 +202,16 @@ pub fn reflow_cjk_paragraphs(
         // Always force paragraph breaks.
         if is_box_drawing_line(probe) {
             if !buffer.is_empty() {
-                segments.push(std::mem::take(&mut buffer));
+                segments.push(ReflowSegment {
+                    kind: buffer_kind,
+                    text: std::mem::take(&mut buffer),
+                });
                 dialog_state.reset();
             }
-            segments.push(stripped_visual.to_string());
+            segments.push(ReflowSegment {
+                kind: SegmentKind::Divider,
+                text: stripped_visual.to_string(),
+            });
             continue;
         }
 
@@ -95,7 +239,10 @@ pub fn reflow_cjk_paragraphs(
 
             // End paragraph → flush buffer (do not emit empty segments)
             if !buffer.is_empty() {
-                segments.push(std::mem::take(&mut buffer));
+                segments.push(ReflowSegment {
+                    kind: buffer_kind,
+                    text: std::mem::take(&mut buffer),
+                });
                 dialog_state.reset();
             }
             continue;
@@ -104,32 +251,44 @@ pub fn reflow_cjk_paragraphs(
         // 5) Page marker lines
         if is_page_marker(heading_probe) {
             if !buffer.is_empty() {
-                segments.push(std::mem::take(&mut buffer));
+                segments.push(ReflowSegment {
+                    kind: buffer_kind,
+                    text: std::mem::take(&mut buffer),
+                });
                 dialog_state.reset();
             }
-            segments.push(line_text.clone());
+            segments.push(ReflowSegment {
+                kind: SegmentKind::PageMarker,
+                text: line_text.clone(),
+            });
             continue;
         }
 
         // 6) Heading / metadata detection
-        let is_title_heading = is_title_heading_line(heading_probe);
-        let is_short_heading = is_heading_like(&line_text);
-        let is_metadata = is_metadata_line(&line_text);
+        let is_title_heading = is_title_heading_line(heading_probe, config);
+        let is_short_heading = is_heading_like(&line_text, config, policy);
+        let is_metadata = is_metadata_line(&line_text, config);
 
-        let mut flush_buffer_and_emit_standalone = |line: &str| {
+        let mut flush_buffer_and_emit_standalone = |line: &str, kind: SegmentKind| {
             if !buffer.is_empty() {
-                segments.push(std::mem::take(&mut buffer));
+                segments.push(ReflowSegment {
+                    kind: buffer_kind,
+                    text: std::mem::take(&mut buffer),
+                });
                 dialog_state.reset();
             }
-            segments.push(line.to_owned());
+            segments.push(ReflowSegment {
+                kind,
+                text: line.to_owned(),
+            });
         };
 
         if is_metadata {
-            flush_buffer_and_emit_standalone(&line_text);
+            flush_buffer_and_emit_standalone(&line_text, SegmentKind::Metadata);
             continue;
         }
         if is_title_heading {
-            flush_buffer_and_emit_standalone(&line_text);
+            flush_buffer_and_emit_standalone(&line_text, SegmentKind::TitleHeading);
             continue;
         }
 
@@ -141,7 +300,7 @@ pub fn reflow_cjk_paragraphs(
             if !buffer.is_empty() {
                 // let buf_text = buffer.as_str();
 
-                if has_unclosed_bracket(buffer_text) {
+                if crate::punct_sets::has_unclosed_bracket(buffer_text, policy) {
                     // treat as continuation
                 } else {
                     let bt = buffer_text.trim_end();
@@ -153,19 +312,31 @@ pub fn reflow_cjk_paragraphs(
                             if is_all_cjk && !CJK_PUNCT_END.contains(&last) {
                                 // continuation
                             } else {
-                                segments.push(std::mem::take(&mut buffer));
+                                segments.push(ReflowSegment {
+                                    kind: buffer_kind,
+                                    text: std::mem::take(&mut buffer),
+                                });
                                 dialog_state.reset();
-                                segments.push(line_text.clone());
+                                segments.push(ReflowSegment {
+                                    kind: SegmentKind::ShortHeading,
+                                    text: line_text.clone(),
+                                });
                                 continue;
                             }
                         }
                     } else {
-                        segments.push(line_text.clone());
+                        segments.push(ReflowSegment {
+                            kind: SegmentKind::ShortHeading,
+                            text: line_text.clone(),
+                        });
                         continue;
                     }
                 }
             } else {
-                segments.push(line_text.clone());
+                segments.push(ReflowSegment {
+                    kind: SegmentKind::ShortHeading,
+                    text: line_text.clone(),
+                });
                 continue;
             }
         }
@@ -176,7 +347,10 @@ pub fn reflow_cjk_paragraphs(
             if let Some(last) = stripped.chars().rev().next() {
                 if is_strong_sentence_end(last) {
                     buffer.push_str(&line_text);
-                    segments.push(std::mem::take(&mut buffer));
+                    segments.push(ReflowSegment {
+                        kind: buffer_kind,
+                        text: std::mem::take(&mut buffer),
+                    });
                     dialog_state.reset();
                     dialog_state.update(&line_text);
                     continue;
@@ -189,6 +363,7 @@ pub fn reflow_cjk_paragraphs(
 
         // First line of a new paragraph
         if buffer.is_empty() {
+            buffer_kind = paragraph_kind_for(&line_text);
             buffer.push_str(&line_text);
             dialog_state.reset();
             dialog_state.update(&line_text);
@@ -201,14 +376,22 @@ pub fn reflow_cjk_paragraphs(
             let last = trimmed_buffer.chars().rev().next();
             if let Some(ch) = last {
                 if ch != '，' && ch != ',' && ch != '、' {
-                    segments.push(std::mem::take(&mut buffer));
+                    segments.push(ReflowSegment {
+                        kind: buffer_kind,
+                        text: std::mem::take(&mut buffer),
+                    });
+                    buffer_kind = paragraph_kind_for(&line_text);
                     buffer.push_str(&line_text);
                     dialog_state.reset();
                     dialog_state.update(&line_text);
                     continue;
                 }
             } else {
-                segments.push(std::mem::take(&mut buffer));
+                segments.push(ReflowSegment {
+                    kind: buffer_kind,
+                    text: std::mem::take(&mut buffer),
+                });
+                buffer_kind = paragraph_kind_for(&line_text);
                 buffer.push_str(&line_text);
                 dialog_state.reset();
                 dialog_state.update(&line_text);
@@ -231,8 +414,14 @@ pub fn reflow_cjk_paragraphs(
         }
 
         // 8a) Strong sentence boundary (handles 。！？, OCR . / :, “.”)
-        if !dialog_state.is_unclosed() && ends_with_sentence_boundary(buffer_text) {
-            segments.push(std::mem::take(&mut buffer));
+        if !dialog_state.blocks_flush()
+            && crate::punct_sets::ends_with_sentence_boundary(buffer_text, policy)
+        {
+            segments.push(ReflowSegment {
+                kind: buffer_kind,
+                text: std::mem::take(&mut buffer),
+            });
+            buffer_kind = paragraph_kind_for(&line_text);
             buffer.push_str(&line_text);
             dialog_state.reset();
             dialog_state.update(&line_text);
@@ -240,8 +429,12 @@ pub fn reflow_cjk_paragraphs(
         }
 
         // 8b) Balanced CJK bracket boundary: （完）, 【番外】, 《後記》
-        if !dialog_state.is_unclosed() && ends_with_cjk_bracket_boundary(buffer_text) {
-            segments.push(std::mem::take(&mut buffer));
+        if !dialog_state.blocks_flush() && ends_with_cjk_bracket_boundary(buffer_text) {
+            segments.push(ReflowSegment {
+                kind: buffer_kind,
+                text: std::mem::take(&mut buffer),
+            });
+            buffer_kind = paragraph_kind_for(&line_text);
             buffer.push_str(&line_text);
             dialog_state.reset();
             dialog_state.update(&line_text);
@@ -249,8 +442,12 @@ pub fn reflow_cjk_paragraphs(
         }
 
         // 8c) Broad punctuation fallback
-        if !dialog_state.is_unclosed() && buffer_ends_with_cjk_punct(buffer_text) {
-            segments.push(std::mem::take(&mut buffer));
+        if !dialog_state.blocks_flush() && buffer_ends_with_cjk_punct(buffer_text) {
+            segments.push(ReflowSegment {
+                kind: buffer_kind,
+                text: std::mem::take(&mut buffer),
+            });
+            buffer_kind = paragraph_kind_for(&line_text);
             buffer.push_str(&line_text);
             dialog_state.reset();
             dialog_state.update(&line_text);
@@ -258,8 +455,12 @@ pub fn reflow_cjk_paragraphs(
         }
 
         // 9) Chapter-like ending lines
-        if is_chapter_ending_line(buffer_text) {
-            segments.push(std::mem::take(&mut buffer));
+        if is_chapter_ending_line(buffer_text, config) {
+            segments.push(ReflowSegment {
+                kind: buffer_kind,
+                text: std::mem::take(&mut buffer),
+            });
+            buffer_kind = paragraph_kind_for(&line_text);
             buffer.push_str(&line_text);
             dialog_state.reset();
             dialog_state.update(&line_text);
@@ -272,18 +473,966 @@ pub fn reflow_cjk_paragraphs(
     }
 
     if !buffer.is_empty() {
-        segments.push(buffer);
+        segments.push(ReflowSegment {
+            kind: buffer_kind,
+            text: buffer,
+        });
     }
 
-    let result = if compact {
-        segments.join("\n")
-    } else {
-        segments.join("\n\n")
-    };
+    if let Some(width) = wrap_width {
+        for segment in &mut segments {
+            if width == 0 || matches!(segment.kind, SegmentKind::Divider | SegmentKind::PageMarker) {
+                continue;
+            }
+            segment.text = wrap_paragraph_kinsoku(&segment.text, width);
+        }
+    }
+
+    segments
+}
+
+/// Heading-prefix style for `reflow_cjk_paragraphs`'s `heading_style` option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeadingStyle {
+    /// `#`, `##`, `###`, ... (one `#` per level, 1-indexed).
+    Markdown,
+    /// `*`, `**`, `***`, ... (one `*` per level, 1-indexed).
+    Org,
+}
+
+/// Prefixes every `TitleHeading`/`ShortHeading` segment with a level
+/// marker, reusing [`crate::book_struct::heading_level`] so the rendered
+/// levels agree with what `build_outline()` reports for the same text.
+/// Headings `heading_level` doesn't recognize a marker for fall back to
+/// the chapter level (3), since that's the common case for short,
+/// keyword-only headings like "后记" or "番外".
+fn apply_heading_style(segments: &mut [ReflowSegment], style: HeadingStyle) {
+    for segment in segments.iter_mut() {
+        if !matches!(
+            segment.kind,
+            SegmentKind::TitleHeading | SegmentKind::ShortHeading
+        ) {
+            continue;
+        }
+
+        let level = crate::book_struct::heading_level(&segment.text).unwrap_or(3);
+        let marker = match style {
+            HeadingStyle::Markdown => "#".repeat(level as usize + 1),
+            HeadingStyle::Org => "*".repeat(level as usize + 1),
+        };
+        segment.text = format!("{marker} {}", segment.text);
+    }
+}
+
+fn config_from_py_params(
+    extra_heading_keywords: Option<Vec<String>>,
+    extra_chapter_markers: Option<Vec<char>>,
+    extra_metadata_keys: Option<Vec<String>>,
+    custom_patterns: Option<Vec<String>>,
+) -> ReflowConfig {
+    ReflowConfig {
+        extra_heading_keywords: extra_heading_keywords.unwrap_or_default(),
+        extra_chapter_markers: extra_chapter_markers.unwrap_or_default(),
+        extra_metadata_keys: extra_metadata_keys.unwrap_or_default(),
+        custom_patterns: custom_patterns.unwrap_or_default(),
+    }
+}
+
+fn policy_from_py_params(
+    treat_colon_as_boundary: bool,
+    treat_ellipsis_as_boundary: bool,
+    enable_ocr_ascii_punct: bool,
+    allow_postfix_closer: bool,
+    pessimistic_brackets: bool,
+) -> crate::punct_sets::ReflowPolicy {
+    crate::punct_sets::ReflowPolicy {
+        treat_colon_as_boundary,
+        treat_ellipsis_as_boundary,
+        enable_ocr_ascii_punct,
+        allow_postfix_closer,
+        pessimistic_brackets,
+    }
+}
+
+/// Reflow CJK paragraphs from PDF-extracted text.
+///
+/// This merges artificial line breaks while preserving paragraphs,
+/// headings, chapter lines, and dialog structure. Thin wrapper that
+/// renders the segments from the same underlying pass (see
+/// [`reflow_cjk_segments`] for its structured, per-segment form) back to
+/// a single string.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Raw text (usually from `extract_pdf_text()`).
+/// add_pdf_page_header : bool
+///     If `false`, try to skip page-break-like blank lines that are not
+///     preceded by CJK punctuation. If `true`, keep those gaps.
+/// compact : bool
+///     If `true`, paragraphs are joined with a single newline ("p1\\np2").
+///     If `false`, paragraphs are separated by a blank line ("p1\\n\\np2").
+/// wrap_width : int, optional
+///     If given, re-break each assembled paragraph to this target display
+///     column width (CJK ideographs, CJK/fullwidth punctuation and forms,
+///     kana and Hangul count as 2 columns, everything else as 1), applying kinsoku line-breaking rules so punctuation/bracket
+///     closers never start a line and opening brackets/quotes never end
+///     one. Box-drawing divider lines and page markers are always emitted
+///     verbatim, unwrapped. When omitted, paragraphs are left as one line
+///     each, same as before.
+/// extra_heading_keywords : list[str], optional
+///     Additional heading keywords, checked the same way as the built-in
+///     list (line must start with one of them).
+/// extra_chapter_markers : list[str], optional
+///     Additional single-character chapter markers, checked alongside the
+///     built-in set (e.g. '章', '回').
+/// extra_metadata_keys : list[str], optional
+///     Additional "Key: value" metadata keys, checked alongside the
+///     built-in set.
+/// custom_patterns : list[str], optional
+///     Substrings that, if present anywhere in a candidate line, force it
+///     to be treated as a heading. Plain substring matching, not regex
+///     (see `ReflowConfig`).
+/// normalize_punctuation : bool, default False
+///     If `true`, run a confusable-punctuation pass before heading/dialog
+///     detection: fullwidth Latin letters/digits collapse to halfwidth,
+///     dash/tilde lookalikes unify, and ASCII straight quotes (`"` `'`)
+///     are paired into curly quotes so English-style dialog blocks are
+///     also kept together. Leave `false` (the default) for byte-faithful
+///     output.
+/// heading_style : str, optional
+///     If given, prefix each `TitleHeading`/`ShortHeading` segment with a
+///     level marker: `"markdown"` for `#`/`##`/... or `"org"` for
+///     `*`/`**`/.... The level is the same 0-4 hierarchy (front/back
+///     matter, volume, part, chapter, section) `build_outline()` reports,
+///     rendered as `level + 1` marker characters; a heading that doesn't
+///     match any of `build_outline`'s marker patterns falls back to the
+///     chapter level. Omit (the default) to leave headings unprefixed.
+///
+/// Returns
+/// -------
+/// String
+///     Reflowed text.
+///
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    add_pdf_page_header,
+    compact,
+    wrap_width=None,
+    extra_heading_keywords=None,
+    extra_chapter_markers=None,
+    extra_metadata_keys=None,
+    custom_patterns=None,
+    normalize_punctuation=false,
+    heading_style=None,
+    treat_colon_as_boundary=true,
+    treat_ellipsis_as_boundary=true,
+    enable_ocr_ascii_punct=true,
+    allow_postfix_closer=true,
+    pessimistic_brackets=true,
+))]
+pub fn reflow_cjk_paragraphs(
+    text: &str,
+    add_pdf_page_header: bool,
+    compact: bool,
+    wrap_width: Option<usize>,
+    extra_heading_keywords: Option<Vec<String>>,
+    extra_chapter_markers: Option<Vec<char>>,
+    extra_metadata_keys: Option<Vec<String>>,
+    custom_patterns: Option<Vec<String>>,
+    normalize_punctuation: bool,
+    heading_style: Option<&str>,
+    treat_colon_as_boundary: bool,
+    treat_ellipsis_as_boundary: bool,
+    enable_ocr_ascii_punct: bool,
+    allow_postfix_closer: bool,
+    pessimistic_brackets: bool,
+) -> PyResult<String> {
+    let config = config_from_py_params(
+        extra_heading_keywords,
+        extra_chapter_markers,
+        extra_metadata_keys,
+        custom_patterns,
+    );
+    let policy = policy_from_py_params(
+        treat_colon_as_boundary,
+        treat_ellipsis_as_boundary,
+        enable_ocr_ascii_punct,
+        allow_postfix_closer,
+        pessimistic_brackets,
+    );
+
+    let mut segments = reflow_cjk_segments_impl(
+        text,
+        add_pdf_page_header,
+        wrap_width,
+        &config,
+        normalize_punctuation,
+        &policy,
+    );
+
+    if let Some(style) = heading_style {
+        let style = match style {
+            "markdown" => HeadingStyle::Markdown,
+            "org" => HeadingStyle::Org,
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "unknown heading style '{other}' (expected 'markdown' or 'org')"
+                )))
+            }
+        };
+        apply_heading_style(&mut segments, style);
+    }
+
+    let separator = if compact { "\n" } else { "\n\n" };
+    let result = segments
+        .into_iter()
+        .map(|segment| segment.text)
+        .collect::<Vec<_>>()
+        .join(separator);
 
     Ok(result)
 }
 
+/// Structured sibling of [`reflow_cjk_paragraphs`]: runs the same reflow
+/// pass but returns every segment's [`SegmentKind`] alongside its text
+/// instead of flattening everything into one joined string. Turns the
+/// reflow engine into a document-abstraction layer that downstream tools
+/// (search indexing, outline building, alternate renderers) can drive
+/// without re-parsing the joined output.
+///
+/// Parameters
+/// ----------
+/// Same as `reflow_cjk_paragraphs`, minus `compact` (there is no joining
+/// to configure) and `heading_style` (headings are returned unmarked).
+///
+/// Returns
+/// -------
+/// list[dict]
+///     One dict per segment: `{"kind": str, "text": str}`. `kind` is one
+///     of `"title_heading"`, `"short_heading"`, `"metadata"`,
+///     `"page_marker"`, `"divider"`, `"paragraph"`, `"dialog"`.
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    add_pdf_page_header,
+    wrap_width=None,
+    extra_heading_keywords=None,
+    extra_chapter_markers=None,
+    extra_metadata_keys=None,
+    custom_patterns=None,
+    normalize_punctuation=false,
+    treat_colon_as_boundary=true,
+    treat_ellipsis_as_boundary=true,
+    enable_ocr_ascii_punct=true,
+    allow_postfix_closer=true,
+    pessimistic_brackets=true,
+))]
+pub fn reflow_cjk_segments(
+    py: Python<'_>,
+    text: &str,
+    add_pdf_page_header: bool,
+    wrap_width: Option<usize>,
+    extra_heading_keywords: Option<Vec<String>>,
+    extra_chapter_markers: Option<Vec<char>>,
+    extra_metadata_keys: Option<Vec<String>>,
+    custom_patterns: Option<Vec<String>>,
+    normalize_punctuation: bool,
+    treat_colon_as_boundary: bool,
+    treat_ellipsis_as_boundary: bool,
+    enable_ocr_ascii_punct: bool,
+    allow_postfix_closer: bool,
+    pessimistic_brackets: bool,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let config = config_from_py_params(
+        extra_heading_keywords,
+        extra_chapter_markers,
+        extra_metadata_keys,
+        custom_patterns,
+    );
+    let policy = policy_from_py_params(
+        treat_colon_as_boundary,
+        treat_ellipsis_as_boundary,
+        enable_ocr_ascii_punct,
+        allow_postfix_closer,
+        pessimistic_brackets,
+    );
+
+    let segments = reflow_cjk_segments_impl(
+        text,
+        add_pdf_page_header,
+        wrap_width,
+        &config,
+        normalize_punctuation,
+        &policy,
+    );
+
+    segments
+        .into_iter()
+        .map(|segment| {
+            let dict = PyDict::new(py);
+            dict.set_item("kind", segment.kind.as_str())?;
+            dict.set_item("text", segment.text)?;
+            Ok(dict.unbind())
+        })
+        .collect()
+}
+
+/// Same reflow pass as [`reflow_cjk_segments`], pre-serialized to a JSON
+/// array of `{"kind": ..., "text": ...}` objects, for callers that want
+/// to hand the document model to another process or language rather than
+/// consuming it in Python directly.
+#[pyfunction]
+#[pyo3(signature = (
+    text,
+    add_pdf_page_header,
+    wrap_width=None,
+    extra_heading_keywords=None,
+    extra_chapter_markers=None,
+    extra_metadata_keys=None,
+    custom_patterns=None,
+    normalize_punctuation=false,
+    treat_colon_as_boundary=true,
+    treat_ellipsis_as_boundary=true,
+    enable_ocr_ascii_punct=true,
+    allow_postfix_closer=true,
+    pessimistic_brackets=true,
+))]
+pub fn reflow_cjk_segments_json(
+    text: &str,
+    add_pdf_page_header: bool,
+    wrap_width: Option<usize>,
+    extra_heading_keywords: Option<Vec<String>>,
+    extra_chapter_markers: Option<Vec<char>>,
+    extra_metadata_keys: Option<Vec<String>>,
+    custom_patterns: Option<Vec<String>>,
+    normalize_punctuation: bool,
+    treat_colon_as_boundary: bool,
+    treat_ellipsis_as_boundary: bool,
+    enable_ocr_ascii_punct: bool,
+    allow_postfix_closer: bool,
+    pessimistic_brackets: bool,
+) -> PyResult<String> {
+    let config = config_from_py_params(
+        extra_heading_keywords,
+        extra_chapter_markers,
+        extra_metadata_keys,
+        custom_patterns,
+    );
+    let policy = policy_from_py_params(
+        treat_colon_as_boundary,
+        treat_ellipsis_as_boundary,
+        enable_ocr_ascii_punct,
+        allow_postfix_closer,
+        pessimistic_brackets,
+    );
+
+    let segments = reflow_cjk_segments_impl(
+        text,
+        add_pdf_page_header,
+        wrap_width,
+        &config,
+        normalize_punctuation,
+        &policy,
+    );
+
+    Ok(segments_to_json(&segments))
+}
+
+/// Extracts and serializes the leading run of bibliographic metadata
+/// (書名/作者/譯者/ISBN/...) from PDF-extracted CJK text as a front-matter
+/// block, the way SiSU converts bespoke document headers into YAML/TOML.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Raw or reflowed text. Only the contiguous metadata lines (per the
+///     same detection `reflow_cjk_paragraphs` uses for `SegmentKind::Metadata`)
+///     at the very top of `text` are collected — that mirrors where these
+///     fields physically sit in scanned books, so a stray "Key: value"
+///     line deeper in the body is left alone rather than misread as
+///     front matter.
+/// extra_metadata_keys : list[str], optional
+///     Additional "Key: value" metadata keys, checked alongside the
+///     built-in `METADATA_KEYS` set (see `ReflowConfig`).
+/// format : str, default "yaml"
+///     `"yaml"` for a `---`-fenced block or `"toml"` for a `+++`-fenced
+///     block. Any other value is an error.
+///
+/// Returns
+/// -------
+/// str
+///     The serialized front-matter block, or the empty string if `text`
+///     doesn't open with any recognized metadata lines.
+#[pyfunction]
+#[pyo3(signature = (text, extra_metadata_keys=None, format="yaml"))]
+pub fn extract_front_matter(
+    text: &str,
+    extra_metadata_keys: Option<Vec<String>>,
+    format: &str,
+) -> PyResult<String> {
+    let config = ReflowConfig {
+        extra_metadata_keys: extra_metadata_keys.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let format = match format {
+        "yaml" => FrontMatterFormat::Yaml,
+        "toml" => FrontMatterFormat::Toml,
+        other => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown front-matter format '{other}' (expected 'yaml' or 'toml')"
+            )))
+        }
+    };
+
+    let fields = extract_metadata(text, &config);
+    Ok(render_front_matter(&fields, format))
+}
+
+/// Collects the leading run of metadata lines (per `is_metadata_line`)
+/// from `text`, normalizing each key to its canonical form via
+/// [`canonical_metadata_key`]. Stops at the first non-metadata or blank
+/// line, since these fields conventionally sit in one contiguous header
+/// block before the body text starts.
+pub fn extract_metadata(text: &str, config: &ReflowConfig) -> Vec<MetadataField> {
+    let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut fields = Vec::new();
+
+    for raw_line in normalized.split('\n') {
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() {
+            if fields.is_empty() {
+                continue;
+            }
+            break;
+        }
+
+        match split_metadata_line(trimmed, config) {
+            Some((key, value)) => fields.push(MetadataField {
+                key: canonical_metadata_key(&key),
+                value,
+            }),
+            None => break,
+        }
+    }
+
+    fields
+}
+
+/// Splits a line recognized by `is_metadata_line` into its raw key and
+/// value, trimming the separator and surrounding whitespace. Returns
+/// `None` if `line` isn't a metadata line at all.
+fn split_metadata_line(line: &str, config: &ReflowConfig) -> Option<(String, String)> {
+    if !is_metadata_line(line, config) {
+        return None;
+    }
+
+    let (sep_byte_idx, sep_char) = line
+        .char_indices()
+        .find(|&(_, ch)| METADATA_SEPARATORS.contains(&ch))?;
+
+    let key = line[..sep_byte_idx].trim().to_owned();
+    let value = line[sep_byte_idx + sep_char.len_utf8()..].trim().to_owned();
+    Some((key, value))
+}
+
+/// Maps a raw `METADATA_KEYS` entry to its canonical field name, merging
+/// the Traditional/Simplified and phrasing variants the built-in set
+/// tracks separately (e.g. 書名/书名, 責任編輯/责任编辑/編輯/编辑/責編/责编)
+/// into one name, so a front-matter block never ends up with both a
+/// `title` and a `書名` field for the same book. Keys outside the
+/// built-in set (i.e. only recognized via `ReflowConfig::extra_metadata_keys`)
+/// pass through unchanged.
+fn canonical_metadata_key(key: &str) -> String {
+    match key {
+        "書名" | "书名" => "title",
+        "作者" => "author",
+        "譯者" | "译者" => "translator",
+        "校訂" | "校订" => "editor_review",
+        "出版社" => "publisher",
+        "出版時間" | "出版时间" | "出版日期" => "publication_date",
+        "版權" | "版权" | "版權頁" | "版权页" | "版權信息" | "版权信息" => "copyright",
+        "責任編輯" | "责任编辑" | "編輯" | "编辑" | "責編" | "责编" => "editor",
+        "定價" | "定价" => "price",
+        "前言" => "preface",
+        "序章" => "prologue",
+        "終章" | "终章" => "final_chapter",
+        "尾聲" | "尾声" => "epilogue",
+        "後記" | "后记" => "afterword",
+        "品牌方" => "brand",
+        "出品方" => "producer",
+        "授權方" | "授权方" => "licensor",
+        "電子版權" | "数字版权" => "digital_rights",
+        "掃描" | "扫描" => "scan_credit",
+        "OCR" => "ocr_credit",
+        "CIP" => "cip",
+        "在版編目" | "在版编目" => "cip_catalog",
+        "分類號" | "分类号" => "classification_number",
+        "主題詞" | "主题词" => "subject_keywords",
+        "發行日" | "发行日" => "release_date",
+        "初版" => "edition",
+        "ISBN" => "isbn",
+        other => return other.to_owned(),
+    }
+    .to_owned()
+}
+
+/// Renders extracted metadata as a fenced front-matter block: `---` for
+/// YAML (Jekyll/Hugo style), `+++` for TOML. Values are double-quoted
+/// scalars, escaped with [`push_json_escaped`] — the same `"`/`\`/control-
+/// character escaping JSON uses is also valid inside a YAML or TOML
+/// double-quoted string.
+pub fn render_front_matter(fields: &[MetadataField], format: FrontMatterFormat) -> String {
+    if fields.is_empty() {
+        return String::new();
+    }
+
+    let fence = match format {
+        FrontMatterFormat::Yaml => "---",
+        FrontMatterFormat::Toml => "+++",
+    };
+    let assign = match format {
+        FrontMatterFormat::Yaml => ": \"",
+        FrontMatterFormat::Toml => " = \"",
+    };
+
+    let mut out = String::new();
+    out.push_str(fence);
+    out.push('\n');
+    for field in fields {
+        out.push_str(&field.key);
+        out.push_str(assign);
+        push_json_escaped(&mut out, &field.value);
+        out.push_str("\"\n");
+    }
+    out.push_str(fence);
+    out.push('\n');
+    out
+}
+
+/// Splice mode for [`reflow_cjk_notes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteMode {
+    /// Splice each note body in parentheses immediately after its
+    /// in-text marker.
+    Inline,
+    /// Replace each in-text marker with an unambiguous `[id]` form and
+    /// gather all note bodies into one trailing segment, keyed by ID.
+    Collect,
+}
+
+/// Headings that introduce a note section. [`reflow_cjk_notes`] skips a
+/// line exactly matching one of these (and any box-drawing divider) when
+/// scanning for note-body lines, but doesn't require one to be present —
+/// a note-body line is still recognized by its marker alone.
+const NOTE_SECTION_HEADINGS: &[&str] = &["註釋", "注釈", "注释"];
+
+/// One in-text note-marker occurrence recorded by [`reflow_cjk_notes`]'s
+/// first pass: `id` is the number the marker itself encodes (e.g. `③` or
+/// `〔3〕` → `3`), and `byte_start`/`byte_end` locate it within `line_idx`.
+struct NoteMarkerOccurrence {
+    id: u32,
+    line_idx: usize,
+    byte_start: usize,
+    byte_end: usize,
+}
+
+/// Matches a note marker starting exactly at byte offset `at` in `s`:
+/// a circled digit (①-⑳), a bracketed numeral (`〔3〕`, `（3）`, `(3)`), or
+/// — if given — `custom_prefix` followed by one or more ASCII digits
+/// (e.g. `"★"` recognizes `★3`). Returns the encoded ID and the marker's
+/// byte length.
+fn match_marker_at(s: &str, at: usize, custom_prefix: Option<&str>) -> Option<(u32, usize)> {
+    let rest = s.get(at..)?;
+    let first = rest.chars().next()?;
+
+    if ('\u{2460}'..='\u{2473}').contains(&first) {
+        return Some((first as u32 - 0x2460 + 1, first.len_utf8()));
+    }
+
+    let closer = match first {
+        '〔' => Some('〕'),
+        '（' => Some('）'),
+        '(' => Some(')'),
+        _ => None,
+    };
+    if let Some(closer) = closer {
+        let digits_start = first.len_utf8();
+        let digits_end = rest[digits_start..]
+            .find(|ch: char| !ch.is_ascii_digit())
+            .map_or(rest.len(), |off| digits_start + off);
+        if digits_end > digits_start {
+            if let Ok(id) = rest[digits_start..digits_end].parse::<u32>() {
+                if rest[digits_end..].starts_with(closer) {
+                    return Some((id, digits_end + closer.len_utf8()));
+                }
+            }
+        }
+    }
+
+    if let Some(prefix) = custom_prefix {
+        if !prefix.is_empty() {
+            if let Some(after_prefix) = rest.strip_prefix(prefix) {
+                let digits_end = after_prefix
+                    .find(|ch: char| !ch.is_ascii_digit())
+                    .unwrap_or(after_prefix.len());
+                if digits_end > 0 {
+                    if let Ok(id) = after_prefix[..digits_end].parse::<u32>() {
+                        return Some((id, prefix.len() + digits_end));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Two-pass endnote/footnote reflow for PDF-extracted CJK text, in the
+/// spirit of SiSU's endnote-inlining tool: PDF extraction scatters note
+/// markers through the body and dumps note bodies at page bottoms, and
+/// this stitches the two back together.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Raw or reflowed text.
+/// mode : str, default "inline"
+///     `"inline"` splices each note body in parentheses immediately
+///     after its in-text marker. `"collect"` replaces each in-text
+///     marker with an unambiguous `[id]` form and gathers all note
+///     bodies into one trailing segment, keyed by ID.
+/// custom_marker_prefix : str, optional
+///     An additional marker form beyond the built-in circled digits
+///     (①-⑳) and bracketed numerals (`〔1〕`, `（1）`, `(1)`): a literal
+///     prefix followed by digits, e.g. `"★"` recognizes `★1`, `★2`, ...
+///
+/// Returns
+/// -------
+/// str
+///     `text` with notes inlined/collected. Returned unchanged if no
+///     in-text markers are found, or if the in-text marker count doesn't
+///     exactly match the note-body count — a mismatch means the read is
+///     ambiguous, and splicing anyway risks corrupting the text.
+#[pyfunction]
+#[pyo3(signature = (text, mode="inline", custom_marker_prefix=None))]
+pub fn reflow_cjk_notes(
+    text: &str,
+    mode: &str,
+    custom_marker_prefix: Option<&str>,
+) -> PyResult<String> {
+    let mode = match mode {
+        "inline" => NoteMode::Inline,
+        "collect" => NoteMode::Collect,
+        other => {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "unknown note mode '{other}' (expected 'inline' or 'collect')"
+            )))
+        }
+    };
+
+    Ok(reflow_cjk_notes_impl(text, mode, custom_marker_prefix))
+}
+
+fn reflow_cjk_notes_impl(text: &str, mode: NoteMode, custom_marker_prefix: Option<&str>) -> String {
+    let normalized = normalize_line_endings(text);
+    let lines: Vec<&str> = normalized.split('\n').collect();
+
+    let mut in_text: Vec<NoteMarkerOccurrence> = Vec::new();
+    let mut bodies: HashMap<u32, String> = HashMap::new();
+    let mut body_line_idx: HashSet<usize> = HashSet::new();
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if is_box_drawing_line(trimmed) || NOTE_SECTION_HEADINGS.contains(&trimmed) {
+            continue;
+        }
+
+        let after_indent = line.trim_start_matches(|ch| ch == ' ' || ch == '\u{3000}');
+
+        if let Some((id, marker_len)) = match_marker_at(after_indent, 0, custom_marker_prefix) {
+            let body_text = after_indent[marker_len..].trim();
+            if !body_text.is_empty() && display_width(after_indent) <= 200 {
+                bodies.insert(id, body_text.to_owned());
+                body_line_idx.insert(line_idx);
+                continue;
+            }
+        }
+
+        let mut byte_idx = 0usize;
+        while byte_idx < line.len() {
+            if let Some((id, marker_len)) = match_marker_at(line, byte_idx, custom_marker_prefix) {
+                in_text.push(NoteMarkerOccurrence {
+                    id,
+                    line_idx,
+                    byte_start: byte_idx,
+                    byte_end: byte_idx + marker_len,
+                });
+                byte_idx += marker_len;
+            } else {
+                byte_idx += line[byte_idx..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+    }
+
+    if in_text.is_empty()
+        || bodies.len() != in_text.len()
+        || !in_text.iter().all(|occ| bodies.contains_key(&occ.id))
+    {
+        return text.to_owned();
+    }
+
+    match mode {
+        NoteMode::Inline => splice_notes_inline(&lines, &in_text, &bodies, &body_line_idx),
+        NoteMode::Collect => collect_notes(&lines, &in_text, &bodies, &body_line_idx),
+    }
+}
+
+/// Groups `in_text` occurrences by the line they appear on.
+fn group_markers_by_line(
+    in_text: &[NoteMarkerOccurrence],
+) -> HashMap<usize, Vec<&NoteMarkerOccurrence>> {
+    let mut by_line: HashMap<usize, Vec<&NoteMarkerOccurrence>> = HashMap::new();
+    for occ in in_text {
+        by_line.entry(occ.line_idx).or_default().push(occ);
+    }
+    for occurrences in by_line.values_mut() {
+        occurrences.sort_by_key(|occ| occ.byte_start);
+    }
+    by_line
+}
+
+fn splice_notes_inline(
+    lines: &[&str],
+    in_text: &[NoteMarkerOccurrence],
+    bodies: &HashMap<u32, String>,
+    body_line_idx: &HashSet<usize>,
+) -> String {
+    let by_line = group_markers_by_line(in_text);
+
+    let mut out_lines: Vec<String> = Vec::with_capacity(lines.len());
+    for (line_idx, line) in lines.iter().enumerate() {
+        if body_line_idx.contains(&line_idx) {
+            continue;
+        }
+
+        match by_line.get(&line_idx) {
+            None => out_lines.push((*line).to_owned()),
+            Some(occurrences) => {
+                let mut rebuilt = String::with_capacity(line.len() + 32);
+                let mut cursor = 0usize;
+                for occ in occurrences {
+                    rebuilt.push_str(&line[cursor..occ.byte_end]);
+                    if let Some(body) = bodies.get(&occ.id) {
+                        rebuilt.push('(');
+                        rebuilt.push_str(body);
+                        rebuilt.push(')');
+                    }
+                    cursor = occ.byte_end;
+                }
+                rebuilt.push_str(&line[cursor..]);
+                out_lines.push(rebuilt);
+            }
+        }
+    }
+
+    out_lines.join("\n")
+}
+
+fn collect_notes(
+    lines: &[&str],
+    in_text: &[NoteMarkerOccurrence],
+    bodies: &HashMap<u32, String>,
+    body_line_idx: &HashSet<usize>,
+) -> String {
+    let by_line = group_markers_by_line(in_text);
+
+    let mut out_lines: Vec<String> = Vec::with_capacity(lines.len());
+    for (line_idx, line) in lines.iter().enumerate() {
+        if body_line_idx.contains(&line_idx) {
+            continue;
+        }
+
+        match by_line.get(&line_idx) {
+            None => out_lines.push((*line).to_owned()),
+            Some(occurrences) => {
+                let mut rebuilt = String::with_capacity(line.len() + 8);
+                let mut cursor = 0usize;
+                for occ in occurrences {
+                    rebuilt.push_str(&line[cursor..occ.byte_start]);
+                    rebuilt.push('[');
+                    rebuilt.push_str(&occ.id.to_string());
+                    rebuilt.push(']');
+                    cursor = occ.byte_end;
+                }
+                rebuilt.push_str(&line[cursor..]);
+                out_lines.push(rebuilt);
+            }
+        }
+    }
+
+    let mut ids: Vec<u32> = bodies.keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut result = out_lines.join("\n");
+    result.push_str("\n\n");
+    for id in ids {
+        result.push('[');
+        result.push_str(&id.to_string());
+        result.push_str("] ");
+        result.push_str(&bodies[&id]);
+        result.push('\n');
+    }
+    while result.ends_with('\n') {
+        result.pop();
+    }
+
+    result
+}
+
+/// Renders segments as a JSON array of `{"kind": ..., "text": ...}`
+/// objects. Hand-rolled rather than pulling in `serde_json` for two
+/// fields — same "no dependency for something this small" call as the
+/// substring matching in [`ReflowConfig`].
+pub fn segments_to_json(segments: &[ReflowSegment]) -> String {
+    let mut out = String::with_capacity(segments.len() * 32 + 2);
+    out.push('[');
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"kind\":\"");
+        out.push_str(segment.kind.as_str());
+        out.push_str("\",\"text\":\"");
+        push_json_escaped(&mut out, &segment.text);
+        out.push_str("\"}");
+    }
+    out.push(']');
+    out
+}
+
+/// Appends `s` to `out`, escaping the characters JSON string literals
+/// require (`"`, `\`, and control characters).
+fn push_json_escaped(out: &mut String, s: &str) {
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Re-breaks a single assembled paragraph to `wrap_width` display columns,
+/// applying kinsoku (Japanese/Chinese line-breaking) constraints: a line
+/// may never start with closing punctuation/brackets or end with an
+/// opening bracket/quote, and a run of Latin letters/digits only breaks
+/// at a space, unless the line holds no space to break at, in which case
+/// the word is broken at the width limit. Wrapped lines are joined with `\n`.
+fn wrap_paragraph_kinsoku(text: &str, wrap_width: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let n = chars.len();
+    if n == 0 {
+        return text.to_owned();
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut i = 0usize;
+
+    while i < n {
+        // Greedily accumulate display width up to the target.
+        let mut width = 0usize;
+        let mut j = i;
+        while j < n {
+            let w = char_display_width(chars[j]);
+            if width + w > wrap_width && j > i {
+                break;
+            }
+            width += w;
+            j += 1;
+        }
+
+        if j >= n {
+            lines.push(chars[i..n].iter().collect());
+            break;
+        }
+
+        // Candidate break: before chars[j].
+        let mut break_at = j;
+
+        // Latin words break only at spaces: if the break would land inside
+        // a run of Latin letters/digits, back up to the last space.
+        if is_latin_word_char(chars[break_at - 1]) && is_latin_word_char(chars[break_at]) {
+            let mut k = break_at;
+            while k > i && chars[k - 1] != ' ' {
+                k -= 1;
+            }
+            if k > i {
+                break_at = k;
+            }
+        }
+
+        // Kinsoku: don't start the next line with forbidden punctuation —
+        // pull it back onto the current line even if that overflows.
+        while break_at < n && break_at > i && is_forbidden_line_start(chars[break_at]) {
+            break_at += 1;
+        }
+
+        // Kinsoku: don't end the current line on an opening bracket/quote —
+        // push it down onto the next line instead.
+        while break_at > i + 1 && is_forbidden_line_end(chars[break_at - 1]) {
+            break_at -= 1;
+        }
+
+        if break_at <= i {
+            break_at = j.max(i + 1);
+        }
+
+        let mut line: String = chars[i..break_at].iter().collect();
+        if line.ends_with(' ') {
+            line.pop();
+        }
+        lines.push(line);
+
+        i = break_at;
+        while i < n && chars[i] == ' ' {
+            i += 1;
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Closing brackets/quotes and trailing punctuation that must never open a
+/// wrapped line.
+const FORBIDDEN_LINE_START: &[char] = &[
+    '」', '』', '）', '】', '》', '〕', '〉', '。', '、', '，', '！', '？', '：', '；', '”', '’',
+];
+
+/// Opening brackets/quotes that must never close a wrapped line.
+const FORBIDDEN_LINE_END: &[char] = &['「', '『', '（', '【', '《', '“', '‘'];
+
+#[inline]
+fn is_forbidden_line_start(ch: char) -> bool {
+    FORBIDDEN_LINE_START.contains(&ch)
+}
+
+#[inline]
+fn is_forbidden_line_end(ch: char) -> bool {
+    FORBIDDEN_LINE_END.contains(&ch)
+}
+
+#[inline]
+fn is_latin_word_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric()
+}
+
 // ---------------------------------------------------------------------------
 // Constants and helpers (copied from your original)
 // ---------------------------------------------------------------------------
@@ -411,22 +1560,12 @@ const BRACKET_PAIRS: &[(char, char)] = &[
     ('〖', '〗'),
 ];
 
-#[inline]
-fn is_bracket_opener(ch: char) -> bool {
-    BRACKET_PAIRS.iter().any(|&(open, _)| open == ch)
-}
-
-#[inline]
-fn is_bracket_closer(ch: char) -> bool {
-    BRACKET_PAIRS.iter().any(|&(_, close)| close == ch)
-}
-
 #[inline]
 fn is_matching_bracket(open: char, close: char) -> bool {
     BRACKET_PAIRS.iter().any(|&(o, c)| o == open && c == close)
 }
 
-fn is_metadata_line(line: &str) -> bool {
+fn is_metadata_line(line: &str, config: &ReflowConfig) -> bool {
     let s = line.trim();
     if s.is_empty() || s.chars().count() > 30 {
         return false;
@@ -452,7 +1591,7 @@ fn is_metadata_line(line: &str) -> bool {
     };
 
     let key = s[..sep_byte_idx].trim();
-    if !METADATA_KEYS.contains(key) {
+    if !METADATA_KEYS.contains(key) && !config.extra_metadata_keys.iter().any(|k| k == key) {
         return false;
     }
 
@@ -512,13 +1651,40 @@ fn is_page_marker(s: &str) -> bool {
     s.starts_with("=== ") && s.ends_with("===")
 }
 
-fn is_title_heading_line(s: &str) -> bool {
+/// Visual (terminal-column) width of a string: CJK ideographs and other
+/// fullwidth characters count as 2 columns, everything else counts as 1.
+/// Mirrors the common `unicode-width` East-Asian-width approach without
+/// adding an external dependency, so a mixed CJK-title-plus-English-aside
+/// line is measured the way it actually renders rather than by raw
+/// `chars().count()`.
+fn display_width(s: &str) -> usize {
+    s.chars().map(char_display_width).sum()
+}
+
+fn char_display_width(ch: char) -> usize {
+    let c = ch as u32;
+    if is_cjk_bmp(ch)
+        || (0x3000..=0x303F).contains(&c) // CJK symbols & punctuation
+        || (0xFF00..=0xFFEF).contains(&c) // fullwidth forms / halfwidth katakana
+        || (0x2E80..=0x2FDF).contains(&c) // CJK radicals / Kangxi radicals
+        || (0x3040..=0x30FF).contains(&c) // hiragana / katakana
+        || (0xAC00..=0xD7A3).contains(&c) // hangul syllables
+    {
+        2
+    } else {
+        1
+    }
+}
+
+fn is_title_heading_line(s: &str, config: &ReflowConfig) -> bool {
     let s = s.trim();
     if s.is_empty() {
         return false;
     }
-    let char_count = s.chars().count();
-    if char_count > 50 {
+    if config.matches_custom_pattern(s) {
+        return true;
+    }
+    if display_width(s) > 50 {
         return false;
     }
 
@@ -532,6 +1698,11 @@ fn is_title_heading_line(s: &str) -> bool {
             return true;
         }
     }
+    for kw in &config.extra_heading_keywords {
+        if !kw.is_empty() && s.starts_with(kw.as_str()) {
+            return true;
+        }
+    }
 
     if let Some(rest) = s.strip_prefix("番外") {
         return rest.chars().count() <= 15;
@@ -543,13 +1714,50 @@ fn is_title_heading_line(s: &str) -> bool {
         if let (Some(first), Some(second)) = (it.next(), it.next()) {
             if (first == '卷' || first == '章')
                 && CJK_NUMERALS.contains(&second)
-                && char_count <= 17
+                && display_width(s) <= 17
             {
                 return true;
             }
         }
     }
 
+    // "<marker> <numeral>" without "第", e.g. "卷 Ⅱ", "章 12".
+    {
+        let mut it = s.chars();
+        if let Some(first) = it.next() {
+            if config.is_chapter_marker(first) {
+                let rest = it.as_str().trim_start();
+                if !rest.is_empty()
+                    && (is_roman_numeral_token(rest) || rest.chars().all(|c| c.is_ascii_digit()))
+                {
+                    return true;
+                }
+            }
+        }
+    }
+
+    // ASCII keyword forms: "Chapter 5", "Part III", "Volume II".
+    {
+        let lower = s.to_ascii_lowercase();
+        for prefix in ["chapter", "part", "volume"] {
+            if let Some(rest) = lower.strip_prefix(prefix) {
+                let rest = rest.trim_start();
+                if !rest.is_empty()
+                    && (rest.chars().all(|c| c.is_ascii_digit()) || is_roman_numeral_token(rest))
+                {
+                    return true;
+                }
+            }
+        }
+    }
+
+    // Dotted-digit outline numbers: "1", "1.1", "2.3.4 小節名".
+    if let Some(rest) = strip_leading_outline_number(s) {
+        if rest.chars().count() <= 40 {
+            return true;
+        }
+    }
+
     let chars: Vec<char> = s.chars().collect();
 
     for i in 0..chars.len() {
@@ -565,7 +1773,7 @@ fn is_title_heading_line(s: &str) -> bool {
                 break;
             }
             let ch = chars[j];
-            if !CHAPTER_MARKERS.contains(&ch) {
+            if !config.is_chapter_marker(ch) {
                 continue;
             }
 
@@ -584,9 +1792,59 @@ fn is_title_heading_line(s: &str) -> bool {
     false
 }
 
-fn is_chapter_ending_line(s: &str) -> bool {
+/// True for a Roman-numeral heading token: either the Unicode Roman
+/// numeral block (Ⅰ Ⅱ … Ⅹ, ⅰ ⅱ … ⅹ) or a bare run of ASCII `I`/`V`/`X`
+/// letters (e.g. "III", "xii").
+fn is_roman_numeral_token(s: &str) -> bool {
+    if s.is_empty() {
+        return false;
+    }
+    if s.chars().all(|c| matches!(c, '\u{2160}'..='\u{2182}')) {
+        return true;
+    }
+    s.chars().all(|c| matches!(c, 'I' | 'V' | 'X' | 'i' | 'v' | 'x'))
+}
+
+/// Strip a leading outline number matching `^\d+(\.\d+)*` followed by
+/// whitespace or end-of-string, returning the remainder (trimmed).
+fn strip_leading_outline_number(s: &str) -> Option<&str> {
+    let bytes = s.as_bytes();
+    let mut i = 0usize;
+
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i == 0 {
+        return None;
+    }
+
+    loop {
+        if i < bytes.len() && bytes[i] == b'.' {
+            let mut j = i + 1;
+            let mut saw_digit = false;
+            while j < bytes.len() && bytes[j].is_ascii_digit() {
+                saw_digit = true;
+                j += 1;
+            }
+            if !saw_digit {
+                break;
+            }
+            i = j;
+        } else {
+            break;
+        }
+    }
+
+    if i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+        return None;
+    }
+
+    Some(s[i..].trim_start())
+}
+
+fn is_chapter_ending_line(s: &str, config: &ReflowConfig) -> bool {
     let s = s.trim();
-    if s.is_empty() || s.chars().count() > 15 {
+    if s.is_empty() || display_width(s) > 15 {
         return false;
     }
 
@@ -605,7 +1863,7 @@ fn is_chapter_ending_line(s: &str) -> bool {
     trimmed
         .chars()
         .last()
-        .is_some_and(|last| CHAPTER_MARKERS.contains(&last))
+        .is_some_and(|last| config.is_chapter_marker(last))
 }
 
 fn is_dialog_start(s: &str) -> bool {
@@ -616,19 +1874,28 @@ fn is_dialog_start(s: &str) -> bool {
         .is_some_and(|ch| is_dialog_opener(ch))
 }
 
-fn is_heading_like(s: &str) -> bool {
+fn is_heading_like(s: &str, config: &ReflowConfig, policy: &crate::punct_sets::ReflowPolicy) -> bool {
     let s = s.trim();
     if s.is_empty() {
         return false;
     }
+    if config.matches_custom_pattern(s) {
+        return true;
+    }
     if s.starts_with("=== ") && s.ends_with("===") {
         return false;
     }
 
-    if has_unclosed_bracket(s) {
+    if crate::punct_sets::has_unclosed_bracket(s, policy) {
         return false;
     }
 
+    // Dotted-digit outline numbers: a bare "1.1" is heading-like on its own;
+    // "1.1 小節名" is heading-like if its title remainder is.
+    if let Some(rest) = strip_leading_outline_number(s) {
+        return rest.is_empty() || is_heading_like(rest, config, policy);
+    }
+
     // If the whole line is wrapped by a matching bracket pair, treat as heading-like.
     // Examples: （第一章）, 【序章】, 《后记》, 〈楔子〉
     if let (Some(first), Some(last)) = (s.chars().next(), s.chars().rev().next()) {
@@ -645,7 +1912,7 @@ fn is_heading_like(s: &str) -> bool {
         }
     }
 
-    let len = s.chars().count();
+    let width = display_width(s);
     let max_len = if is_all_ascii(s) || is_mixed_cjk_ascii(s) {
         16
     } else {
@@ -653,7 +1920,7 @@ fn is_heading_like(s: &str) -> bool {
     };
 
     if let Some(last) = s.chars().last() {
-        if (last == '：' || last == ':') && len < max_len {
+        if (last == '：' || last == ':') && width < max_len {
             let body = strip_last_char(s);
             if is_all_cjk_no_ws(body) {
                 return true;
@@ -668,7 +1935,7 @@ fn is_heading_like(s: &str) -> bool {
         return false;
     }
 
-    if len <= max_len {
+    if width <= max_len {
         if s.chars().any(|ch| CJK_PUNCT_END.contains(&ch)) {
             return false;
         }
@@ -835,182 +2102,13 @@ fn is_digit_ascii_or_fullwidth(ch: char) -> bool {
     ch >= '０' && ch <= '９'
 }
 
-#[inline]
-pub fn has_unclosed_bracket(s: &str) -> bool {
-    let mut has_open = false;
-    let mut has_close = false;
-
-    for ch in s.chars() {
-        has_open |= is_bracket_opener(ch);
-        has_close |= is_bracket_closer(ch);
-        if has_open && has_close {
-            break;
-        }
-    }
-
-    has_open && !has_close
-}
-
 // ------ Sentence Boundary start ------ //
 
-/// Level-2 normalized sentence boundary detection, INCLUDING OCR artifacts:
-/// - ASCII '.' / ':' at end-of-line in mostly-CJK text (treat like '。' / '：')
-/// - ASCII '.' before closers: `“.”` / `.」` / `.）` (treat like '。' before quote/bracket)
-///
-/// Assumptions (already in your codebase):
-/// - `is_mostly_cjk(s: &str) -> bool`
-/// - `is_dialog_closer(ch: char) -> bool`
-/// - `is_cjk(ch: char) -> bool`
-pub fn ends_with_sentence_boundary(s: &str) -> bool {
-    if s.trim().is_empty() {
-        return false;
-    }
-
-    let last_non_ws = match find_last_non_whitespace_char_index(s) {
-        Some(i) => i,
-        None => return false,
-    };
-
-    let last = nth_char(s, last_non_ws);
-
-    // 1) Strong sentence enders.
-    if is_strong_sentence_end(last) {
-        return true;
-    }
-
-    // 2) Level-2 ALSO accepts OCR '.' / ':' at line end (mostly-CJK).
-    //    (This is what your C# "case 2 / case 3" does in level>=3, but you want it in level=2.)
-    if (last == '.' || last == ':') && is_ocr_cjk_ascii_punct_at_line_end(s, last_non_ws) {
-        return true;
-    }
-
-    // 3) Quote closers after strong end, plus OCR artifact `.“”` / `.」` / `.）`.
-    if is_quote_closer(last) {
-        if let Some(prev_non_ws) = find_prev_non_whitespace_char_index(s, last_non_ws) {
-            let prev = nth_char(s, prev_non_ws);
-
-            // Strong end immediately before quote closer.
-            if is_strong_sentence_end(prev) {
-                return true;
-            }
-
-            // OCR artifact: ASCII '.' before closers.
-            if prev == '.' && is_ocr_cjk_ascii_punct_before_closers(s, prev_non_ws) {
-                return true;
-            }
-
-            // (Optional) If also want OCR ':' before closers like `“:”`, enable this:
-            // if prev == ':' && is_ocr_cjk_ascii_punct_before_closers(s, prev_non_ws) { return true; }
-        }
-    }
-
-    // 4) Bracket closers with mostly CJK.
-    if is_bracket_closer(last) && last_non_ws > 0 && is_mostly_cjk(s) {
-        return true;
-    }
-
-    // 5) Ellipsis as weak boundary.
-    if ends_with_ellipsis(s) {
-        return true;
-    }
-
-    false
-}
-
-#[inline]
-fn nth_char(s: &str, idx: usize) -> char {
-    s.chars().nth(idx).unwrap_or('\0')
-}
-
-#[inline]
-fn is_quote_closer(ch: char) -> bool {
-    is_dialog_closer(ch)
-}
-
 #[inline]
 fn is_strong_sentence_end(ch: char) -> bool {
     matches!(ch, '。' | '！' | '？' | '!' | '?')
 }
 
-/// Last non-whitespace char index (char index).
-fn find_last_non_whitespace_char_index(s: &str) -> Option<usize> {
-    let mut char_pos = s.chars().count();
-
-    for ch in s.chars().rev() {
-        char_pos -= 1;
-        if !ch.is_whitespace() {
-            return Some(char_pos);
-        }
-    }
-    None
-}
-
-/// Previous non-whitespace char index strictly before `end_exclusive` (char index).
-fn find_prev_non_whitespace_char_index(s: &str, end_exclusive: usize) -> Option<usize> {
-    let mut char_pos = end_exclusive;
-
-    // IMPORTANT: reverse AFTER take() is unsafe on some toolchains,
-    // so we manually limit using a counter instead.
-    for ch in s.chars().rev() {
-        if char_pos == 0 {
-            break;
-        }
-        char_pos -= 1;
-        if !ch.is_whitespace() {
-            return Some(char_pos);
-        }
-    }
-    None
-}
-
-/// Strict OCR: punct itself is at end-of-line (only whitespace after it),
-/// and preceded by CJK in a mostly-CJK line.
-fn is_ocr_cjk_ascii_punct_at_line_end(s: &str, punct_index: usize) -> bool {
-    if punct_index == 0 {
-        return false;
-    }
-    if !is_at_line_end_ignoring_whitespace(s, punct_index) {
-        return false;
-    }
-    let prev = nth_char(s, punct_index - 1);
-    is_cjk_bmp(prev) && is_mostly_cjk(s)
-}
-
-/// Relaxed OCR: after punct, allow only whitespace and closers (quote/bracket).
-/// This enables `“.”` / `.」` / `.）` to count as sentence boundary.
-fn is_ocr_cjk_ascii_punct_before_closers(s: &str, punct_index: usize) -> bool {
-    if punct_index == 0 {
-        return false;
-    }
-    if !is_at_end_allowing_closers(s, punct_index) {
-        return false;
-    }
-    let prev = nth_char(s, punct_index - 1);
-    is_cjk_bmp(prev) && is_mostly_cjk(s)
-}
-
-fn is_at_line_end_ignoring_whitespace(s: &str, index: usize) -> bool {
-    s.chars().skip(index + 1).all(|c| c.is_whitespace())
-}
-
-fn is_at_end_allowing_closers(s: &str, index: usize) -> bool {
-    for ch in s.chars().skip(index + 1) {
-        if ch.is_whitespace() {
-            continue;
-        }
-        if is_quote_closer(ch) || is_bracket_closer(ch) {
-            continue;
-        }
-        return false;
-    }
-    true
-}
-
-fn ends_with_ellipsis(s: &str) -> bool {
-    let t = s.trim_end();
-    t.ends_with('…') || t.ends_with("……") || t.ends_with("...") || t.ends_with("..")
-}
-
 // ------ Sentence Boundary end ------ //
 
 // ------ Bracket Boundary start ------ //
@@ -1053,35 +2151,61 @@ pub fn ends_with_cjk_bracket_boundary(s: &str) -> bool {
     is_bracket_type_balanced(t, open, close)
 }
 
+/// A real push/pop-if-match-else-mismatch nesting stack, not a delegation
+/// to [`DialogState`]: `DialogState`'s stack is typed over
+/// [`DialogBracketKind`], a closed set of 6 quote/corner-bracket openers
+/// used for dialog tracking across buffered lines, and has no variant for
+/// the much broader CJK bracket set (`（）【】《》〖〕〈⟩` etc.) that
+/// [`ends_with_cjk_bracket_boundary`] has to check, scoped to one
+/// already-assembled candidate string rather than a running document. So
+/// this pushes `open` itself on the stack and, on `close`, pops only if
+/// the top is `open` — a closer with nothing of this pair open is an
+/// immediate mismatch (malformed OCR) rather than a floored counter —
+/// same mechanism as `DialogState::update`, independently instantiated
+/// because the element types are disjoint.
 #[inline]
 fn is_bracket_type_balanced(s: &str, open: char, close: char) -> bool {
-    let mut depth: i32 = 0;
+    let mut stack: Vec<char> = Vec::new();
 
     for ch in s.chars() {
         if ch == open {
-            depth += 1;
+            stack.push(ch);
         } else if ch == close {
-            depth -= 1;
-            if depth < 0 {
-                // Closing before opening → malformed OCR
-                return false;
+            match stack.last() {
+                Some(&top) if top == open => {
+                    stack.pop();
+                }
+                _ => {
+                    // Closing before opening → malformed OCR, i.e. a mismatch.
+                    return false;
+                }
             }
         }
     }
 
-    depth == 0
+    stack.is_empty()
 }
 
 // ------ Bracket Boundary end ------ //
+/// Stage 1: strip invisible bidi/zero-width/BOM/soft-hyphen control
+/// codepoints (see [`crate::punct_sets::sanitize_control_chars`]) before
+/// any of the repeat-collapsing heuristics below see the line — left in
+/// place, they're neither whitespace nor CJK and can desync the token
+/// splitting this function does on `split_whitespace`.
 fn collapse_repeated_segments(line: &str) -> String {
-    let trimmed = line.trim();
+    let (sanitized, _removed) = crate::punct_sets::sanitize_control_chars(
+        line,
+        crate::punct_sets::ControlCharPolicy::Strip,
+    );
+
+    let trimmed = sanitized.trim();
     if trimmed.is_empty() {
-        return line.to_owned();
+        return sanitized.into_owned();
     }
 
     let parts: Vec<&str> = trimmed.split_whitespace().collect();
     if parts.is_empty() {
-        return line.to_owned();
+        return sanitized.into_owned();
     }
 
     let phrase_collapsed = collapse_repeated_word_sequences(&parts);
@@ -1093,63 +2217,87 @@ fn collapse_repeated_segments(line: &str) -> String {
     token_collapsed.join(" ")
 }
 
-fn collapse_repeated_word_sequences(parts: &[&str]) -> Vec<String> {
-    const MIN_REPEATS: usize = 3;
-    const MAX_PHRASE_LEN: usize = 8;
-
-    let n = parts.len();
-    if n < MIN_REPEATS {
-        return parts.iter().map(|s| (*s).to_owned()).collect();
-    }
-
-    for start in 0..n {
-        for phrase_len in 1..=MAX_PHRASE_LEN {
-            if start + phrase_len > n {
+/// Collapses every maximal tandem repeat in `items` to a single period and
+/// reports how many items that removed.
+///
+/// Scans left to right; at each position tries candidate periods `p` in
+/// ascending order (the first, smallest period whose span reaches the
+/// threshold wins, so "abababab" collapses to "ab", never to "abab"),
+/// extending each candidate as long as `items[i] == items[i + p]`.
+/// Once a candidate's span covers at least `min_repeats * p` items it is
+/// collapsed to its first `p` items and the scan resumes after the whole
+/// span; otherwise the current item is kept as-is and the scan advances by
+/// one. This single routine is what both [`collapse_repeated_token`] (over
+/// `char`s) and [`collapse_repeated_word_sequences`] (over words) are
+/// built on, so a period-11 OCR stutter or a plain 2x doubling are caught
+/// the same way a whole-token quadruple-repeat is.
+fn collapse_tandem_repeats<T: Clone + PartialEq>(
+    items: &[T],
+    min_repeats: usize,
+) -> (Vec<T>, usize) {
+    let n = items.len();
+    let mut result = Vec::with_capacity(n);
+    let mut removed = 0usize;
+    let mut i = 0;
+
+    while i < n {
+        let max_period = (n - i) / min_repeats;
+        let mut best: Option<(usize, usize)> = None;
+
+        for p in 1..=max_period {
+            let mut extend = 0;
+            while i + p + extend < n && items[i + extend] == items[i + p + extend] {
+                extend += 1;
+            }
+            let span = p + extend;
+            if span >= min_repeats * p {
+                best = Some((p, span));
                 break;
             }
+        }
 
-            let mut count = 1;
-
-            loop {
-                let next_start = start + count * phrase_len;
-                if next_start + phrase_len > n {
-                    break;
-                }
-
-                let mut equal = true;
-                for k in 0..phrase_len {
-                    if parts[start + k] != parts[next_start + k] {
-                        equal = false;
-                        break;
-                    }
-                }
-                if !equal {
-                    break;
-                }
-                count += 1;
+        match best {
+            Some((p, span)) => {
+                result.extend_from_slice(&items[i..i + p]);
+                removed += span - p;
+                i += span;
             }
-
-            if count >= MIN_REPEATS {
-                let mut result = Vec::with_capacity(n - (count - 1) * phrase_len);
-                for i in 0..start {
-                    result.push(parts[i].to_owned());
-                }
-                for k in 0..phrase_len {
-                    result.push(parts[start + k].to_owned());
-                }
-                let tail_start = start + count * phrase_len;
-                for i in tail_start..n {
-                    result.push(parts[i].to_owned());
-                }
-                return result;
+            None => {
+                result.push(items[i].clone());
+                i += 1;
             }
         }
     }
 
-    parts.iter().map(|s| (*s).to_owned()).collect()
+    (result, removed)
+}
+
+/// Collapses repeated word runs (a phrase stuttered ≥3 times, e.g. a
+/// scanner re-reading the same line of dialogue) via [`collapse_tandem_repeats`].
+/// Three repeats, not two, is the threshold here — unlike single-token
+/// repeats, a doubled word or short phrase can be intentional prose
+/// ("very very tired"), so two occurrences alone aren't enough evidence of
+/// an OCR artifact.
+fn collapse_repeated_word_sequences(parts: &[&str]) -> Vec<String> {
+    const MIN_REPEATS: usize = 3;
+
+    let owned: Vec<String> = parts.iter().map(|s| (*s).to_owned()).collect();
+    if owned.len() < MIN_REPEATS {
+        return owned;
+    }
+
+    collapse_tandem_repeats(&owned, MIN_REPEATS).0
 }
 
+/// Collapses repeated runs within a single token (e.g. `abababab` →
+/// `ab`) via [`collapse_tandem_repeats`], with `min_repeats = 2` since a
+/// within-token doubling is essentially never intentional. Tokens shorter
+/// than 4 chars or longer than 200 are returned unchanged — too short to
+/// usefully repeat, and long enough that the O(n²) scan isn't worth it on
+/// a token this pass should never see.
 fn collapse_repeated_token(token: &str) -> String {
+    const MIN_REPEATS: usize = 2;
+
     let chars: Vec<char> = token.chars().collect();
     let length = chars.len();
 
@@ -1157,93 +2305,367 @@ fn collapse_repeated_token(token: &str) -> String {
         return token.to_owned();
     }
 
-    for unit_len in 4..=10 {
-        if unit_len > length / 3 {
-            break;
-        }
-        if length % unit_len != 0 {
-            continue;
-        }
+    collapse_tandem_repeats(&chars, MIN_REPEATS).0.into_iter().collect()
+}
+
+/// Standalone, caller-tunable form of the tandem-repeat collapsing that
+/// [`collapse_repeated_segments`] runs internally during reflow: collapses
+/// whitespace-split phrase repeats first, then whole-token repeats within
+/// what's left, via the same [`collapse_tandem_repeats`] engine, and
+/// reports how many characters that removed.
+///
+/// Unlike the internal pass (which is hardwired to a 3-repeat phrase
+/// threshold and a 2-repeat token threshold to stay conservative inside
+/// the bigger reflow pipeline), this exposes a single `min_repeats`
+/// threshold for both passes, so a caller fighting an unusual corpus (a
+/// 2x phrase doubling, or a long fixed-period stutter) can dial it down
+/// without forking the heuristics.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Text to collapse. Treated as one whitespace-split sequence, so
+///     this is meant for a single line or short snippet rather than a
+///     multi-paragraph document.
+/// min_repeats : int, default 2
+///     Minimum number of period repeats (`k`) a run must have before it's
+///     collapsed to one period.
+///
+/// Returns
+/// -------
+/// Tuple[str, int]
+///     The collapsed text, and the number of characters removed.
+#[pyfunction]
+#[pyo3(signature = (text, min_repeats=2))]
+pub fn collapse_repeated_runs(text: &str, min_repeats: usize) -> (String, usize) {
+    let min_repeats = min_repeats.max(1);
+    let (sanitized, _removed) = crate::punct_sets::sanitize_control_chars(
+        text,
+        crate::punct_sets::ControlCharPolicy::Strip,
+    );
+
+    let trimmed = sanitized.trim();
+    if trimmed.is_empty() {
+        return (sanitized.into_owned(), 0);
+    }
+    let original_len = trimmed.chars().count();
 
-        let unit = &chars[0..unit_len];
-        let repeat_count = length / unit_len;
+    let parts: Vec<String> = trimmed.split_whitespace().map(str::to_owned).collect();
+    let phrase_collapsed = if parts.len() >= min_repeats {
+        collapse_tandem_repeats(&parts, min_repeats).0
+    } else {
+        parts
+    };
 
-        let mut all_match = true;
-        for i in 1..repeat_count {
-            let start = i * unit_len;
-            let end = start + unit_len;
-            if &chars[start..end] != unit {
-                all_match = false;
-                break;
+    let token_collapsed: Vec<String> = phrase_collapsed
+        .into_iter()
+        .map(|tok| {
+            let chars: Vec<char> = tok.chars().collect();
+            if chars.len() < min_repeats {
+                return tok;
             }
+            collapse_tandem_repeats(&chars, min_repeats)
+                .0
+                .into_iter()
+                .collect()
+        })
+        .collect();
+
+    let collapsed = token_collapsed.join(" ");
+    let removed = original_len.saturating_sub(collapsed.chars().count());
+    (collapsed, removed)
+}
+
+/// One quote/corner-bracket type [`DialogState`] tracks, used as the
+/// stack element for its unified nesting stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DialogBracketKind {
+    DoubleQuote,
+    SingleQuote,
+    Corner,
+    CornerBold,
+    CornerTop,
+    CornerWide,
+}
+
+impl DialogBracketKind {
+    fn opener(self) -> char {
+        match self {
+            DialogBracketKind::DoubleQuote => '“',
+            DialogBracketKind::SingleQuote => '‘',
+            DialogBracketKind::Corner => '「',
+            DialogBracketKind::CornerBold => '『',
+            DialogBracketKind::CornerTop => '﹁',
+            DialogBracketKind::CornerWide => '﹃',
+        }
+    }
+
+    fn for_opener(ch: char) -> Option<Self> {
+        match ch {
+            '“' => Some(DialogBracketKind::DoubleQuote),
+            '‘' => Some(DialogBracketKind::SingleQuote),
+            '「' => Some(DialogBracketKind::Corner),
+            '『' => Some(DialogBracketKind::CornerBold),
+            '﹁' => Some(DialogBracketKind::CornerTop),
+            '﹃' => Some(DialogBracketKind::CornerWide),
+            _ => None,
         }
+    }
 
-        if all_match {
-            return unit.iter().collect();
+    fn for_closer(ch: char) -> Option<Self> {
+        match ch {
+            '”' => Some(DialogBracketKind::DoubleQuote),
+            '’' => Some(DialogBracketKind::SingleQuote),
+            '」' => Some(DialogBracketKind::Corner),
+            '』' => Some(DialogBracketKind::CornerBold),
+            '﹂' => Some(DialogBracketKind::CornerTop),
+            '﹄' => Some(DialogBracketKind::CornerWide),
+            _ => None,
         }
     }
+}
+
+/// One entry on [`DialogState`]'s nesting stack: which type is open, and
+/// the byte offset (relative to the start of the span since the last
+/// [`DialogState::reset`]) where its opener was seen.
+#[derive(Debug, Clone, Copy)]
+struct OpenDialogBracket {
+    kind: DialogBracketKind,
+    byte_offset: usize,
+}
 
-    token.to_owned()
+/// A closer [`DialogState::update`] saw that didn't match the top of the
+/// stack — either a stray closer with nothing open, or one that closed
+/// the wrong type (e.g. `「…”`).
+#[derive(Debug, Clone, Copy)]
+struct DialogBracketMismatch {
+    byte_offset: usize,
+    found: char,
 }
 
+/// Tracks nested quotes/corner brackets across the reflow loop's buffered
+/// lines with a single stack shared by every type, rather than one
+/// per-type counter: a closer pops the stack only if the top is its
+/// matching opener, so mis-nesting like `「…“…」…”` is caught as a
+/// mismatch instead of each counter independently flooring at zero and
+/// reporting "nothing's open" for both pairs.
 struct DialogState {
-    double_quote: i32,
-    single_quote: i32,
-    corner: i32,
-    corner_bold: i32,
-    corner_top: i32,
-    corner_wide: i32,
+    stack: Vec<OpenDialogBracket>,
+    mismatches: Vec<DialogBracketMismatch>,
+    bytes_consumed: usize,
+    // Toggle state for pairing ASCII straight quotes into curly quotes
+    // (see `normalize_punctuation_line`). Not tracked on the stack
+    // directly: once paired, the emitted curly quote goes through the
+    // stack like any other opener/closer.
+    ascii_double_open: bool,
+    ascii_single_open: bool,
 }
 
 impl DialogState {
     fn new() -> Self {
         Self {
-            double_quote: 0,
-            single_quote: 0,
-            corner: 0,
-            corner_bold: 0,
-            corner_top: 0,
-            corner_wide: 0,
+            stack: Vec::new(),
+            mismatches: Vec::new(),
+            bytes_consumed: 0,
+            ascii_double_open: false,
+            ascii_single_open: false,
         }
     }
 
     fn reset(&mut self) {
-        self.double_quote = 0;
-        self.single_quote = 0;
-        self.corner = 0;
-        self.corner_bold = 0;
-        self.corner_top = 0;
-        self.corner_wide = 0;
+        self.stack.clear();
+        self.mismatches.clear();
+        self.bytes_consumed = 0;
+        self.ascii_double_open = false;
+        self.ascii_single_open = false;
+    }
+
+    /// Pairs the next ASCII `"` into an opening or closing curly quote,
+    /// alternating on each call.
+    fn pair_ascii_double(&mut self) -> char {
+        self.ascii_double_open = !self.ascii_double_open;
+        if self.ascii_double_open {
+            '“'
+        } else {
+            '”'
+        }
+    }
+
+    /// Pairs the next ASCII `'` (once apostrophes have been filtered out)
+    /// into an opening or closing curly quote, alternating on each call.
+    fn pair_ascii_single(&mut self) -> char {
+        self.ascii_single_open = !self.ascii_single_open;
+        if self.ascii_single_open {
+            '‘'
+        } else {
+            '’'
+        }
     }
 
+    /// Pushes on any tracked opener; on a tracked closer, pops only if the
+    /// top of the stack is its matching opener. A closer that doesn't
+    /// match (nothing open, or the wrong type on top) is recorded in
+    /// [`Self::mismatches`] and the stack is left untouched, instead of a
+    /// per-type counter silently "recovering" by flooring at zero.
     fn update(&mut self, s: &str) {
-        for ch in s.chars() {
-            match ch {
-                '“' => self.double_quote += 1,
-                '”' => self.double_quote = (self.double_quote - 1).max(0),
-                '‘' => self.single_quote += 1,
-                '’' => self.single_quote = (self.single_quote - 1).max(0),
-                '「' => self.corner += 1,
-                '」' => self.corner = (self.corner - 1).max(0),
-                '『' => self.corner_bold += 1,
-                '』' => self.corner_bold = (self.corner_bold - 1).max(0),
-                '﹁' => self.corner_top += 1,
-                '﹂' => self.corner_top = (self.corner_top - 1).max(0),
-                '﹃' => self.corner_wide += 1,
-                '﹄' => self.corner_wide = (self.corner_wide - 1).max(0),
-                _ => {}
+        for (byte_offset, ch) in s.char_indices() {
+            let abs_offset = self.bytes_consumed + byte_offset;
+
+            if let Some(kind) = DialogBracketKind::for_opener(ch) {
+                self.stack.push(OpenDialogBracket {
+                    kind,
+                    byte_offset: abs_offset,
+                });
+                continue;
+            }
+
+            if let Some(kind) = DialogBracketKind::for_closer(ch) {
+                match self.stack.last() {
+                    Some(top) if top.kind == kind => {
+                        self.stack.pop();
+                    }
+                    _ => {
+                        self.mismatches.push(DialogBracketMismatch {
+                            byte_offset: abs_offset,
+                            found: ch,
+                        });
+                    }
+                }
             }
         }
+
+        self.bytes_consumed += s.len();
     }
 
     fn is_unclosed(&self) -> bool {
-        self.double_quote > 0
-            || self.single_quote > 0
-            || self.corner > 0
-            || self.corner_bold > 0
-            || self.corner_top > 0
-            || self.corner_wide > 0
+        !self.stack.is_empty()
+    }
+
+    /// Currently-open openers, innermost (most recently opened) first.
+    fn open_openers(&self) -> Vec<char> {
+        self.stack.iter().rev().map(|b| b.kind.opener()).collect()
+    }
+
+    /// Byte offset (relative to the start of the span since the last
+    /// [`Self::reset`]) where the outermost still-unclosed pair began.
+    fn outermost_unclosed_byte_offset(&self) -> Option<usize> {
+        self.stack.first().map(|b| b.byte_offset)
+    }
+
+    /// Byte offset + character of every closer seen since the last
+    /// [`Self::reset`] that didn't match the top of the stack.
+    fn mismatches(&self) -> &[DialogBracketMismatch] {
+        &self.mismatches
+    }
+
+    /// Whether the current nesting should block a boundary-driven flush:
+    /// the stack has a still-open entry, *and* nothing scanned so far
+    /// failed to match it. A recorded [`Self::mismatches`] entry is
+    /// evidence that at least one closer in this span was OCR noise
+    /// (a stray closer, or one that closed the wrong type) rather than
+    /// the remaining open entries being genuinely-nested dialog, so once
+    /// a mismatch has been seen the merge loop stops treating
+    /// [`Self::is_unclosed`] as a reason to keep buffering.
+    fn blocks_flush(&self) -> bool {
+        self.is_unclosed() && self.mismatches().is_empty()
+    }
+}
+
+/// One-shot dialog/corner-bracket nesting scan over `text`, exposing the
+/// same introspection [`DialogState::open_openers`],
+/// [`DialogState::outermost_unclosed_byte_offset`], and
+/// [`DialogState::mismatches`] give the reflow merge loop, for a caller who
+/// just wants to know whether a chunk of OCR'd text has unresolved dialog
+/// nesting without running it through the full reflow pipeline.
+///
+/// Unlike the merge loop, this never resets mid-scan: `text` is treated as
+/// one continuous span, so a caller wanting per-paragraph resets should
+/// call this once per paragraph.
+///
+/// Parameters
+/// ----------
+/// text : str
+///     Text to scan.
+///
+/// Returns
+/// -------
+/// Tuple[List[str], Optional[int], List[Tuple[int, str]]]
+///     `(open_openers, outermost_unclosed_byte_offset, mismatches)` —
+///     `open_openers` lists currently-open openers innermost first;
+///     `mismatches` is `(byte_offset, closer)` for every closer that
+///     didn't match the top of the stack.
+#[pyfunction]
+pub fn scan_dialog_nesting(text: &str) -> (Vec<char>, Option<usize>, Vec<(usize, char)>) {
+    let mut state = DialogState::new();
+    state.update(text);
+
+    let mismatches = state
+        .mismatches()
+        .iter()
+        .map(|m| (m.byte_offset, m.found))
+        .collect();
+
+    (
+        state.open_openers(),
+        state.outermost_unclosed_byte_offset(),
+        mismatches,
+    )
+}
+
+/// Normalizes confusable punctuation, in the spirit of the confusable-char
+/// table rustc's lexer uses for its `unicode_chars` diagnostics: fullwidth
+/// Latin letters/digits (pure OCR width artifacts) collapse to halfwidth,
+/// dash/tilde lookalikes unify, a TeX-style `` `` ``/`''` quote pair
+/// collapses to a CJK corner bracket (same mapping as
+/// [`crate::punct_sets::normalize_confusables`]'s `MULTI_CHAR_CONFUSABLES`
+/// table), and ASCII straight quotes are paired into curly quotes via
+/// `dialog_state`'s open/close toggle so that downstream heading/dialog
+/// detection sees canonical CJK forms. Characters with an established CJK
+/// punctuation role elsewhere in this module (，。！？：；（）etc.) are
+/// left untouched.
+fn normalize_punctuation_line(s: &str, dialog_state: &mut DialogState) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::with_capacity(s.len());
+
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '`' && chars.get(i + 1) == Some(&'`') {
+            out.push('「');
+            i += 2;
+            continue;
+        }
+        if chars[i] == '\'' && chars.get(i + 1) == Some(&'\'') && !is_ascii_apostrophe(&chars, i) {
+            out.push('」');
+            i += 2;
+            continue;
+        }
+
+        match chars[i] {
+            '"' => out.push(dialog_state.pair_ascii_double()),
+            '\'' if !is_ascii_apostrophe(&chars, i) => out.push(dialog_state.pair_ascii_single()),
+            ch => out.push(normalize_confusable_char(ch)),
+        }
+        i += 1;
     }
+
+    out
+}
+
+/// True when `chars[i]` is an ASCII `'` sitting between two alphanumerics
+/// (e.g. "it's", "O'Brien") — an apostrophe, not an opening/closing quote.
+fn is_ascii_apostrophe(chars: &[char], i: usize) -> bool {
+    let prev_alnum = i > 0 && chars[i - 1].is_ascii_alphanumeric();
+    let next_alnum = i + 1 < chars.len() && chars[i + 1].is_ascii_alphanumeric();
+    prev_alnum && next_alnum
+}
+
+/// Maps a single confusable/homoglyph character to its canonical form via
+/// the shared, sorted table in
+/// [`crate::punct_sets::normalize_single_confusable`] (fullwidth Latin
+/// width artifacts, dash/tilde lookalikes, and a handful of halfwidth/
+/// other-script punctuation homoglyphs).
+fn normalize_confusable_char(ch: char) -> char {
+    crate::punct_sets::normalize_single_confusable(ch)
 }
 
 fn strip_halfwidth_indent_keep_fullwidth(s: &str) -> &str {
@@ -1264,3 +2686,91 @@ fn strip_last_char(s: &str) -> &str {
         None => s,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialog_state_blocks_flush_on_genuine_nesting() {
+        let mut state = DialogState::new();
+        state.update("「他说：“你好");
+        assert!(state.is_unclosed());
+        assert!(state.blocks_flush());
+        assert!(state.mismatches().is_empty());
+    }
+
+    #[test]
+    fn test_dialog_state_mismatched_closer_does_not_block_flush() {
+        let mut state = DialogState::new();
+        // "「" opens a corner bracket, but "”" tries to close a double
+        // quote — the wrong type, so it's recorded as a mismatch and the
+        // corner bracket is left open on the stack.
+        state.update("「他说");
+        state.update("”");
+        assert!(state.is_unclosed()); // the corner bracket is still open
+        assert_eq!(state.mismatches().len(), 1);
+        // A mismatch is evidence of OCR noise, so it no longer blocks a flush.
+        assert!(!state.blocks_flush());
+    }
+
+    #[test]
+    fn test_scan_dialog_nesting_reports_open_openers_and_offset() {
+        let (open_openers, outermost_offset, mismatches) = scan_dialog_nesting("「甲说：『乙');
+        assert_eq!(open_openers, vec!['『', '「']);
+        assert_eq!(outermost_offset, Some(0));
+        assert!(mismatches.is_empty());
+    }
+
+    #[test]
+    fn test_reflow_cjk_notes_inline_splices_body_after_marker() {
+        let text = "正文①继续正文。\n①这是注释一。";
+        let result = reflow_cjk_notes(text, "inline", None).unwrap();
+        assert_eq!(result, "正文①(这是注释一。)继续正文。");
+    }
+
+    #[test]
+    fn test_reflow_cjk_notes_collect_gathers_bodies_at_end() {
+        let text = "正文①继续正文。\n①这是注释一。";
+        let result = reflow_cjk_notes(text, "collect", None).unwrap();
+        assert_eq!(result, "正文[1]继续正文。\n\n[1] 这是注释一。");
+    }
+
+    #[test]
+    fn test_reflow_cjk_notes_leaves_text_unchanged_on_marker_body_mismatch() {
+        let text = "正文①继续②正文。\n①这是注释一。";
+        let result = reflow_cjk_notes(text, "inline", None).unwrap();
+        assert_eq!(result, text);
+    }
+
+    #[test]
+    fn test_reflow_cjk_notes_rejects_unknown_mode() {
+        assert!(reflow_cjk_notes("正文①。\n①注。", "bogus", None).is_err());
+    }
+
+    #[test]
+    fn test_extract_front_matter_renders_yaml() {
+        let text = "作者：張三\n\n正文内容。";
+        let result = extract_front_matter(text, None, "yaml").unwrap();
+        assert_eq!(result, "---\nauthor: \"張三\"\n---\n");
+    }
+
+    #[test]
+    fn test_extract_front_matter_rejects_unknown_format() {
+        assert!(extract_front_matter("作者：張三\n\n正文", None, "json").is_err());
+    }
+
+    #[test]
+    fn test_collapse_repeated_runs_collapses_tandem_phrase_repeat() {
+        let (collapsed, removed) = collapse_repeated_runs("救命 救命 救命", 2);
+        assert_eq!(collapsed, "救命");
+        assert!(removed > 0);
+    }
+
+    #[test]
+    fn test_collapse_repeated_runs_leaves_below_threshold_untouched() {
+        let (collapsed, removed) = collapse_repeated_runs("救命 救命", 3);
+        assert_eq!(collapsed, "救命 救命");
+        assert_eq!(removed, 0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Sentence segmentation for CJK/mixed text, built on the same boundary
+//! logic [`crate::reflow`] uses to merge lines — here it's run the other
+//! direction, to split a (usually already-reflowed) paragraph back into
+//! individual sentences.
+//!
+//! `ends_with_sentence_boundary` is a suffix predicate only; this module
+//! turns it into a forward scan so callers can segment OCR'd book text
+//! before per-sentence OpenCC conversion.
+
+use crate::punct_sets::{
+    ends_with_sentence_boundary, has_unclosed_bracket, is_allowed_postfix_closer, is_colon_like,
+    is_dialog_closer, is_strong_sentence_end, ReflowPolicy,
+};
+use pyo3::pyfunction;
+
+/// Splits `s` into sentences using the same boundary rules as
+/// `ends_with_sentence_boundary`, tuned by `policy`.
+///
+/// Scans char-by-char; after each candidate terminator (a strong ender,
+/// colon-like punctuation, ellipsis-forming `.`, or a dialog/bracket
+/// closer), the accumulated slice — including any trailing run of
+/// quote/bracket closers, which stay attached to the sentence they close —
+/// is re-tested with `ends_with_sentence_boundary`. A slice with an
+/// unbalanced bracket run (tracked the same way `has_unclosed_bracket`
+/// does) is never split, even if it ends on what looks like a terminator.
+pub fn split_sentences(s: &str, policy: &ReflowPolicy) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut rest = s;
+
+    while let Some(end) = find_next_sentence_end(rest, policy) {
+        let (sentence, tail) = rest.split_at(end);
+        sentences.push(sentence);
+        rest = tail;
+    }
+
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+
+    sentences
+}
+
+/// Streaming variant of [`split_sentences`]: yields one sentence at a time
+/// instead of collecting the whole `Vec` up front.
+pub fn split_sentences_iter(s: &str, policy: ReflowPolicy) -> SentenceSplit<'_> {
+    SentenceSplit { rest: s, policy }
+}
+
+/// Iterator returned by [`split_sentences_iter`].
+pub struct SentenceSplit<'a> {
+    rest: &'a str,
+    policy: ReflowPolicy,
+}
+
+impl<'a> Iterator for SentenceSplit<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        match find_next_sentence_end(self.rest, &self.policy) {
+            Some(end) => {
+                let (sentence, tail) = self.rest.split_at(end);
+                self.rest = tail;
+                Some(sentence)
+            }
+            None => {
+                let sentence = self.rest;
+                self.rest = "";
+                Some(sentence)
+            }
+        }
+    }
+}
+
+/// True for a character that might end a sentence: a strong ender,
+/// colon-like punctuation, the `.` in an OCR ellipsis/line-end, or a
+/// dialog/bracket closer that could follow one.
+fn is_candidate_terminator(ch: char) -> bool {
+    is_strong_sentence_end(ch)
+        || is_colon_like(ch)
+        || ch == '.'
+        || ch == '…'
+        || is_dialog_closer(ch)
+        || is_allowed_postfix_closer(ch)
+}
+
+/// Finds the byte offset (relative to `s`) where the first sentence ends,
+/// or `None` if `s` contains no complete sentence boundary.
+fn find_next_sentence_end(s: &str, policy: &ReflowPolicy) -> Option<usize> {
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let n = chars.len();
+
+    for i in 0..n {
+        if !is_candidate_terminator(chars[i].1) {
+            continue;
+        }
+
+        // Trailing quote/bracket closers stay attached to this sentence.
+        let mut j = i + 1;
+        while j < n && (is_dialog_closer(chars[j].1) || is_allowed_postfix_closer(chars[j].1)) {
+            j += 1;
+        }
+
+        let end_byte = if j < n { chars[j].0 } else { s.len() };
+        let candidate = &s[..end_byte];
+
+        if has_unclosed_bracket(candidate, policy) {
+            continue;
+        }
+        if ends_with_sentence_boundary(candidate, policy) {
+            return Some(end_byte);
+        }
+    }
+
+    None
+}
+
+/// Splits text into sentences using the same boundary rules the reflow
+/// engine uses, so OCR'd book text can be converted sentence-by-sentence.
+///
+/// Parameters
+/// ----------
+/// text : &str
+///     Text to segment (usually already reflowed into paragraphs).
+/// treat_colon_as_boundary : bool, default True
+/// treat_ellipsis_as_boundary : bool, default True
+/// enable_ocr_ascii_punct : bool, default True
+/// allow_postfix_closer : bool, default True
+/// pessimistic_brackets : bool, default True
+///     [`ReflowPolicy`] fields threaded into `ends_with_sentence_boundary`
+///     and `has_unclosed_bracket`, so splitting can be tuned per document
+///     instead of always running every rule.
+///
+/// Returns
+/// -------
+/// List[str]
+///     One entry per sentence, in document order.
+#[pyfunction]
+#[pyo3(name = "split_sentences")]
+#[pyo3(signature = (
+    text,
+    treat_colon_as_boundary=true,
+    treat_ellipsis_as_boundary=true,
+    enable_ocr_ascii_punct=true,
+    allow_postfix_closer=true,
+    pessimistic_brackets=true,
+))]
+pub fn split_sentences_py(
+    text: &str,
+    treat_colon_as_boundary: bool,
+    treat_ellipsis_as_boundary: bool,
+    enable_ocr_ascii_punct: bool,
+    allow_postfix_closer: bool,
+    pessimistic_brackets: bool,
+) -> Vec<String> {
+    let policy = ReflowPolicy {
+        treat_colon_as_boundary,
+        treat_ellipsis_as_boundary,
+        enable_ocr_ascii_punct,
+        allow_postfix_closer,
+        pessimistic_brackets,
+    };
+    split_sentences(text, &policy)
+        .into_iter()
+        .map(str::to_owned)
+        .collect()
+}